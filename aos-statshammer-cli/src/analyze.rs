@@ -0,0 +1,119 @@
+use crate::format::{Formats, Output, text_table};
+use crate::input::{TargetArgs, load_weapon};
+use crate::overrides::Overrides;
+use anyhow::Result;
+use aos_statshammer_core::sensitivity::{Gain, sensitivity};
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub(crate) struct AnalyzeArgs {
+    #[arg(short, long, value_name = "FILE")]
+    weapon: PathBuf,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl AnalyzeArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let weapon = load_weapon(&self.weapon, &self.overrides, formats)?;
+        let target = self.target.load(&self.overrides, formats)?;
+        let output = AnalyzeOutput::new(
+            weapon.average_damage(&target),
+            &sensitivity(&weapon, &target),
+        );
+        print!("{}", formats.render(&output)?);
+        Ok(())
+    }
+}
+
+/// Every single improvement, from the most to the least damage gained.
+#[derive(Serialize, Debug)]
+struct AnalyzeOutput {
+    average_damage: f64,
+    improvements: Vec<AnalyzeRow>,
+}
+
+#[derive(Serialize, Debug)]
+struct AnalyzeRow {
+    rank: usize,
+    improvement: String,
+    average_damage: f64,
+    gain: f64,
+    /// The gain as a fraction of the damage without the improvement.
+    relative_gain: Option<f64>,
+}
+
+impl AnalyzeOutput {
+    fn new(average_damage: f64, gains: &[Gain]) -> Self {
+        Self {
+            average_damage,
+            improvements: gains
+                .iter()
+                .enumerate()
+                .map(|(i, gain)| AnalyzeRow {
+                    rank: i + 1,
+                    improvement: gain.improvement.to_string(),
+                    average_damage: gain.average_damage,
+                    gain: gain.gain,
+                    relative_gain: gain.relative_gain,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Output for AnalyzeOutput {
+    fn text(&self) -> String {
+        let mut text = format!("Average Damage: {:.3}\n\n", self.average_damage);
+        let headers = ["Rank", "Improvement", "Damage", "Gain", "Gain %"].map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .improvements
+            .iter()
+            .map(|row| {
+                vec![
+                    row.rank.to_string(),
+                    row.improvement.clone(),
+                    format!("{:.3}", row.average_damage),
+                    format!("{:+.3}", row.gain),
+                    row.relative_gain
+                        .map_or("-".to_string(), |g| format!("{:+.1}%", g * 100.0)),
+                ]
+            })
+            .collect();
+        text.push_str(&text_table(&headers, &rows));
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::target::Target;
+    use aos_statshammer_core::weapon::WeaponBuilder;
+
+    #[test]
+    fn ranks_every_improvement() {
+        let weapon = WeaponBuilder::default()
+            .attacks(3)
+            .hit(4)
+            .wound(4)
+            .rend(0)
+            .damage(2)
+            .build()
+            .unwrap();
+        let target = Target::from(3);
+        let output = AnalyzeOutput::new(
+            weapon.average_damage(&target),
+            &sensitivity(&weapon, &target),
+        );
+        let ranks: Vec<_> = output.improvements.iter().map(|row| row.rank).collect();
+        assert_eq!(ranks, (1..=13).collect::<Vec<_>>());
+        assert!(output.text().contains("+1 damage"));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::format::{Formats, Output, text_table};
+use crate::input::{TargetArgs, load_unit};
+use crate::overrides::Overrides;
+use crate::target_config::TargetConfig;
+use anyhow::Result;
+use aos_statshammer_core::attrition::{Attrition, attrition, fight};
+use aos_statshammer_core::combat::{Combatant, Strike};
+use aos_statshammer_core::target::Target;
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub(crate) struct AttritionArgs {
+    /// The attacking unit
+    #[arg(short, long, value_name = "FILE")]
+    unit: PathBuf,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// The number of battle rounds to fight for
+    #[arg(short, long, default_value_t = 5)]
+    rounds: u32,
+
+    /// The target's own unit, to attack back after each of the attacker's attacks
+    #[arg(long, value_name = "FILE", requires = "defence")]
+    retaliate: Option<PathBuf>,
+
+    /// The attacking unit's defensive (target) profile, which the retaliation attacks
+    #[arg(long, value_name = "FILE", requires = "retaliate")]
+    defence: Option<PathBuf>,
+
+    // Applied to the attacking unit and the target (not the retaliation)
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl AttritionArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let (_, attacker) = load_unit(&self.unit, &self.overrides, formats)?;
+        let target = self.target.load(&self.overrides, formats)?;
+        let retaliation = match (&self.retaliate, &self.defence) {
+            (Some(unit), Some(defence)) => {
+                let (_, unit) = load_unit(unit, &Overrides::default(), formats)?;
+                let defence: Target = formats.read::<TargetConfig>(defence)?.try_into()?;
+                Some((unit, defence))
+            }
+            _ => None,
+        };
+        let result = match &retaliation {
+            Some((unit, defence)) => fight(
+                Combatant {
+                    unit: &attacker,
+                    target: defence,
+                    strike: Strike::Normal,
+                },
+                Combatant {
+                    unit,
+                    target: &target,
+                    strike: Strike::Normal,
+                },
+                self.rounds,
+            ),
+            None => attrition(&attacker, &target, self.rounds),
+        };
+        let output = AttritionOutput {
+            retaliation: retaliation.is_some(),
+            result,
+        };
+        print!("{}", formats.render(&output)?);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct AttritionOutput {
+    retaliation: bool,
+    #[serde(flatten)]
+    result: Attrition,
+}
+
+fn percent(probability: f64) -> String {
+    format!("{:.1}%", probability * 100.0)
+}
+
+impl Output for AttritionOutput {
+    fn text(&self) -> String {
+        let mut headers = vec![
+            "Round",
+            "Attacker models",
+            "Target models",
+            "Target destroyed",
+        ];
+        if self.retaliation {
+            headers.push("Attacker destroyed");
+        }
+        let headers: Vec<String> = headers.into_iter().map(String::from).collect();
+        let rows: Vec<Vec<String>> = self
+            .result
+            .rounds
+            .iter()
+            .map(|r| {
+                let mut row = vec![
+                    r.round.to_string(),
+                    format!("{:.2}", r.attacker_models),
+                    format!("{:.2}", r.target_models),
+                    percent(r.target_destroyed),
+                ];
+                if self.retaliation {
+                    row.push(percent(r.attacker_destroyed));
+                }
+                row
+            })
+            .collect();
+        let mut text = text_table(&headers, &rows);
+
+        let headers = ["Destroyed in", "Probability"].map(String::from);
+        let mut rows: Vec<Vec<String>> = self
+            .result
+            .destroyed_in
+            .iter()
+            .enumerate()
+            .map(|(i, p)| vec![format!("round {}", i + 1), percent(*p)])
+            .collect();
+        rows.push(vec!["survives".to_string(), percent(self.result.survives)]);
+        text.push('\n');
+        text.push_str(&text_table(&headers, &rows));
+        text
+    }
+}
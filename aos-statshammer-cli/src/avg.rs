@@ -0,0 +1,122 @@
+use crate::dist::DistributionOutput;
+use crate::explain::{ExplainOutput, explain};
+use crate::format::{Formats, Output};
+use crate::input::{TargetArgs, load_weapon_config};
+use crate::overrides::Overrides;
+use crate::summary;
+use crate::watch;
+use anyhow::{Context, Result};
+use aos_statshammer_core::distribution::Summary;
+use aos_statshammer_core::weapon::Weapon;
+use clap::Args;
+use log::debug;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+// These are also the top level options, used when no subcommand is given (which
+// is why `weapon` is only required at the argument level).
+#[derive(Args, Debug)]
+pub(crate) struct AvgArgs {
+    #[arg(short, long, value_name = "FILE", required = true)]
+    weapon: Option<PathBuf>,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// Re-run the calculation whenever one of the input files changes
+    #[arg(long)]
+    watch: bool,
+
+    /// Show a histogram of the damage distribution
+    #[arg(long)]
+    histogram: bool,
+
+    /// The maximum width of the histogram (in columns)
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        default_value_t = 80,
+        requires = "histogram"
+    )]
+    width: usize,
+
+    /// Show each step of the calculation, and the rule behind each modifier
+    #[arg(long)]
+    explain: bool,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl AvgArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        if self.watch {
+            watch::run(self, formats)
+        } else {
+            print!("{}", self.render(formats)?);
+            Ok(())
+        }
+    }
+
+    /// The files that the output depends on.
+    pub fn input_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = vec![self.weapon()?.to_path_buf()];
+        files.extend(self.target.file().map(PathBuf::from));
+        Ok(files)
+    }
+
+    /// Load the inputs, run the calculation and render the output.
+    pub fn render(&self, formats: &Formats) -> Result<String> {
+        let weapon_config = load_weapon_config(self.weapon()?, &self.overrides, formats)?;
+        let weapon: Weapon = weapon_config.clone().try_into()?;
+        let target = self.target.load(&self.overrides, formats)?;
+        debug!("{:?}", &weapon);
+        debug!("{:?}", &target);
+        let output = AvgOutput {
+            average_damage: weapon.average_damage(&target),
+            summary: weapon.damage_summary(&target),
+            // The histogram was asked for, so the distribution is calculated however long it takes
+            distribution: self
+                .histogram
+                .then(|| DistributionOutput::new(weapon.damage_distribution(&target), self.width)),
+            explanation: self
+                .explain
+                .then(|| explain(&weapon_config, &target))
+                .transpose()?,
+        };
+        formats.render(&output)
+    }
+
+    fn weapon(&self) -> Result<&Path> {
+        self.weapon
+            .as_deref()
+            .context("No weapon specified. Please use --weapon.")
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct AvgOutput {
+    average_damage: f64,
+    /// `None` if the weapon can do too much damage to calculate it quickly
+    summary: Option<Summary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distribution: Option<DistributionOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<ExplainOutput>,
+}
+
+impl Output for AvgOutput {
+    fn text(&self) -> String {
+        let mut text = format!("Average Damage: {:.3}\n", self.average_damage);
+        text.push_str(&summary::text(self.summary.as_ref()));
+        if let Some(distribution) = &self.distribution {
+            text.push('\n');
+            text.push_str(&distribution.histogram());
+        }
+        if let Some(explanation) = &self.explanation {
+            text.push('\n');
+            text.push_str(&explanation.steps());
+        }
+        text
+    }
+}
@@ -0,0 +1,360 @@
+use crate::format::{Formats, Output, text_table};
+use crate::input::label;
+use crate::unit_config::UnitConfig;
+use anyhow::{Context, Result, bail};
+use clap::{Args, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// The prefix for referring to a file in the catalog, e.g. `catalog:stormcast/liberators/warhammer`.
+const PREFIX: &str = "catalog:";
+
+/// The name of the index file, at the root of the catalog.
+const INDEX: &str = "index.toml";
+
+/// The extensions a catalog file may have, in order of preference.
+const EXTENSIONS: [&str; 2] = ["toml", "json"];
+
+/// The part after `catalog:`, if `path` is a catalog reference.
+pub(crate) fn reference(path: &Path) -> Option<&str> {
+    path.to_str()?.strip_prefix(PREFIX)
+}
+
+/// A directory of unit and weapon files, organised by faction:
+///
+/// ```text
+/// <faction>/<unit>.toml
+/// <faction>/<unit>/<weapon>.toml
+/// ```
+///
+/// Which is the same layout written by `import`.
+pub(crate) struct Catalog<'a> {
+    root: &'a Path,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    Unit,
+    Weapon,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Entry {
+    /// What to put after `catalog:` to refer to this file.
+    reference: String,
+    kind: Kind,
+    name: String,
+    /// The unit a weapon belongs to (if there is a unit file for it).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unit: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Index {
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+impl<'a> Catalog<'a> {
+    pub fn new(root: &'a Path) -> Self {
+        Self { root }
+    }
+
+    /// The file for a reference such as `stormcast/liberators/warhammer`.
+    pub fn resolve(&self, reference: &str) -> Result<PathBuf> {
+        let relative = Path::new(reference);
+        if reference.is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        {
+            bail!(
+                "Invalid catalog reference `{PREFIX}{reference}` (expected e.g. `{PREFIX}faction/unit/weapon`)"
+            );
+        }
+        let path = self.root.join(relative);
+        if path.is_file() {
+            return Ok(path);
+        }
+        EXTENSIONS
+            .iter()
+            .map(|ext| path.with_extension(ext))
+            .find(|path| path.is_file())
+            .with_context(|| {
+                format!(
+                    "`{PREFIX}{reference}` is not in the catalog (expected {}.toml)",
+                    path.display()
+                )
+            })
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join(INDEX)
+    }
+
+    /// Every unit and weapon in the catalog, read from the files themselves.
+    fn scan(&self, formats: &Formats) -> Result<Vec<Entry>> {
+        if !self.root.is_dir() {
+            bail!(
+                "There is no catalog at {} (use --catalog to choose a different directory)",
+                self.root.display()
+            );
+        }
+        let mut entries = vec![];
+        for faction in sorted_dir(self.root)? {
+            if !faction.is_dir() {
+                continue;
+            }
+            let mut units = vec![];
+            for unit in sorted_dir(&faction)? {
+                if unit.is_file() && has_catalog_extension(&unit) {
+                    let config: UnitConfig = formats.read(&unit)?;
+                    entries.push(Entry {
+                        reference: self.reference_for(&unit),
+                        kind: Kind::Unit,
+                        name: config.name.clone(),
+                        unit: None,
+                    });
+                    units.push((unit.with_extension(""), config.name));
+                }
+            }
+            for dir in sorted_dir(&faction)? {
+                if !dir.is_dir() {
+                    continue;
+                }
+                let unit = units
+                    .iter()
+                    .find(|(path, _)| *path == dir)
+                    .map(|(_, name)| name.clone());
+                for weapon in sorted_dir(&dir)? {
+                    if weapon.is_file() && has_catalog_extension(&weapon) {
+                        entries.push(Entry {
+                            reference: self.reference_for(&weapon),
+                            kind: Kind::Weapon,
+                            name: label(&weapon),
+                            unit: unit.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// The entries from the index, or from scanning the catalog if it has not been indexed.
+    fn entries(&self, formats: &Formats) -> Result<Vec<Entry>> {
+        let path = self.index_path();
+        if path.is_file() {
+            let index: Index = formats.read(&path)?;
+            Ok(index.entries)
+        } else {
+            self.scan(formats)
+        }
+    }
+
+    fn reference_for(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(self.root).unwrap_or(path);
+        relative
+            .with_extension("")
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+fn sorted_dir(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)
+        .with_context(|| format!("Unable to read {}", dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    // Skip hidden files and directories (e.g. `.git`)
+    paths.retain(|p| {
+        !p.file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+    });
+    paths.sort();
+    Ok(paths)
+}
+
+fn has_catalog_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.contains(&e))
+        && path.file_name().is_some_and(|n| n != INDEX)
+}
+
+/// Whether every word of the query appears in the entry (ignoring case).
+fn matches(entry: &Entry, query: &str) -> bool {
+    let haystack = format!(
+        "{} {} {}",
+        entry.reference,
+        entry.name,
+        entry.unit.as_deref().unwrap_or_default()
+    )
+    .to_lowercase();
+    query
+        .to_lowercase()
+        .split_whitespace()
+        .all(|word| haystack.contains(word))
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct CatalogArgs {
+    #[command(subcommand)]
+    command: CatalogCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum CatalogCommand {
+    /// Search the catalog for units and weapons by name
+    Search {
+        /// The words to search for (every word must match)
+        query: String,
+    },
+    /// Write the index of every unit and weapon in the catalog
+    Index,
+}
+
+impl CatalogArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let catalog = Catalog::new(&formats.catalog);
+        match &self.command {
+            CatalogCommand::Search { query } => {
+                let entries = catalog
+                    .entries(formats)?
+                    .into_iter()
+                    .filter(|entry| matches(entry, query))
+                    .collect();
+                let output = SearchOutput {
+                    query: query.clone(),
+                    entries,
+                };
+                print!("{}", formats.render(&output)?);
+            }
+            CatalogCommand::Index => {
+                let index = Index {
+                    entries: catalog.scan(formats)?,
+                };
+                let path = catalog.index_path();
+                fs::write(&path, toml::to_string(&index)?)
+                    .with_context(|| format!("Unable to write {}", path.display()))?;
+                let count = |kind| index.entries.iter().filter(|e| e.kind == kind).count();
+                let output = IndexOutput {
+                    path,
+                    units: count(Kind::Unit),
+                    weapons: count(Kind::Weapon),
+                };
+                print!("{}", formats.render(&output)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct SearchOutput {
+    query: String,
+    entries: Vec<Entry>,
+}
+
+impl Output for SearchOutput {
+    fn text(&self) -> String {
+        if self.entries.is_empty() {
+            return format!("No matches for `{}`\n", self.query);
+        }
+        let headers = ["Reference", "Kind", "Name", "Unit"].map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let kind = match e.kind {
+                    Kind::Unit => "unit",
+                    Kind::Weapon => "weapon",
+                };
+                vec![
+                    format!("{PREFIX}{}", e.reference),
+                    kind.to_string(),
+                    e.name.clone(),
+                    e.unit.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        text_table(&headers, &rows)
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct IndexOutput {
+    path: PathBuf,
+    units: usize,
+    weapons: usize,
+}
+
+impl Output for IndexOutput {
+    fn text(&self) -> String {
+        format!(
+            "Indexed {} unit(s) and {} weapon(s) in {}\n",
+            self.units,
+            self.weapons,
+            self.path.display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn examples() -> &'static Path {
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/catalog"))
+    }
+
+    #[test]
+    fn resolve_references() {
+        let catalog = Catalog::new(examples());
+        let path = catalog.resolve("stormcast/liberators/warhammer").unwrap();
+        assert_eq!(path, examples().join("stormcast/liberators/warhammer.toml"));
+        assert!(catalog.resolve("stormcast/liberators/axe").is_err());
+        assert!(catalog.resolve("../weapons/simple").is_err());
+        assert!(catalog.resolve("").is_err());
+    }
+
+    #[test]
+    fn references_are_only_prefixed_paths() {
+        assert_eq!(reference(Path::new("catalog:a/b")), Some("a/b"));
+        assert_eq!(reference(Path::new("weapons/simple.toml")), None);
+    }
+
+    #[test]
+    fn scan_finds_units_and_weapons() {
+        let entries = Catalog::new(examples()).scan(&Formats::default()).unwrap();
+        let liberators: Vec<_> = entries
+            .iter()
+            .filter(|e| matches(e, "Liberators"))
+            .map(|e| (e.reference.as_str(), e.kind))
+            .collect();
+        assert_eq!(
+            liberators,
+            [
+                ("stormcast/liberators", Kind::Unit),
+                ("stormcast/liberators/grandhammer", Kind::Weapon),
+                ("stormcast/liberators/warhammer", Kind::Weapon),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_word_must_match() {
+        let entry = Entry {
+            reference: "stormcast/liberators/warhammer".to_string(),
+            kind: Kind::Weapon,
+            name: "warhammer".to_string(),
+            unit: Some("Liberators".to_string()),
+        };
+        assert!(matches(&entry, "liberators WARHAMMER"));
+        assert!(!matches(&entry, "liberators grandhammer"));
+    }
+}
@@ -0,0 +1,307 @@
+use std::fmt::Write;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 450.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 180.0;
+const MARGIN_TOP: f64 = 50.0;
+const MARGIN_BOTTOM: f64 = 60.0;
+const PALETTE: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+];
+
+/// A labelled set of values, one for each category on the x axis.
+#[derive(Debug, Clone)]
+pub(crate) struct Series {
+    pub label: String,
+    pub values: Vec<f64>,
+}
+
+/// The text and data for a chart.
+#[derive(Debug, Clone)]
+pub(crate) struct Chart<'a> {
+    pub title: &'a str,
+    pub x_label: &'a str,
+    pub y_label: &'a str,
+    pub categories: &'a [String],
+    pub series: &'a [Series],
+}
+
+impl Chart<'_> {
+    /// Render as an SVG line chart, with a point for every category.
+    pub fn line_svg(&self) -> String {
+        let axes = Axes::new(self);
+        let mut svg = self.frame(&axes);
+        for (i, series) in self.series.iter().enumerate() {
+            let colour = PALETTE[i % PALETTE.len()];
+            let points: Vec<(f64, f64)> = series
+                .values
+                .iter()
+                .enumerate()
+                .map(|(c, v)| (axes.category_centre(c), axes.y(*v)))
+                .collect();
+            let path = points
+                .iter()
+                .map(|(x, y)| format!("{x:.1},{y:.1}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(
+                svg,
+                r#"<polyline fill="none" stroke="{colour}" stroke-width="2.5" points="{path}"/>"#
+            )
+            .unwrap();
+            for ((x, y), value) in points.iter().zip(&series.values) {
+                writeln!(
+                    svg,
+                    r#"<circle cx="{x:.1}" cy="{y:.1}" r="4" fill="{colour}"><title>{}: {value:.3}</title></circle>"#,
+                    escape(&series.label)
+                )
+                .unwrap();
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Render as an SVG bar chart, with a group of bars (one per series) for
+    /// every category.
+    pub fn bar_svg(&self) -> String {
+        let axes = Axes::new(self);
+        let mut svg = self.frame(&axes);
+        let group_width = axes.category_width() * 0.8;
+        let bar_width = group_width / self.series.len().max(1) as f64;
+        for (i, series) in self.series.iter().enumerate() {
+            let colour = PALETTE[i % PALETTE.len()];
+            for (c, value) in series.values.iter().enumerate() {
+                let x = axes.category_centre(c) - group_width / 2.0 + bar_width * i as f64;
+                let y = axes.y(*value);
+                writeln!(
+                    svg,
+                    r#"<rect x="{x:.1}" y="{y:.1}" width="{:.1}" height="{:.1}" fill="{colour}"><title>{}: {value:.3}</title></rect>"#,
+                    bar_width.max(1.0),
+                    axes.bottom() - y,
+                    escape(&series.label)
+                )
+                .unwrap();
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The SVG header, title, axes, grid lines and legend.
+    fn frame(&self, axes: &Axes) -> String {
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">"#
+        )
+        .unwrap();
+        writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{:.1}" y="28" text-anchor="middle" font-size="18" font-weight="bold">{}</text>"#,
+            (MARGIN_LEFT + axes.right()) / 2.0,
+            escape(self.title)
+        )
+        .unwrap();
+
+        for tick in axes.ticks() {
+            let y = axes.y(tick);
+            writeln!(
+                svg,
+                r##"<line x1="{MARGIN_LEFT}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#e0e0e0"/>"##,
+                axes.right()
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+                MARGIN_LEFT - 8.0,
+                y + 4.0,
+                format_tick(tick)
+            )
+            .unwrap();
+        }
+        for (c, category) in self.categories.iter().enumerate() {
+            writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                axes.category_centre(c),
+                axes.bottom() + 18.0,
+                escape(category)
+            )
+            .unwrap();
+        }
+        writeln!(
+            svg,
+            r#"<polyline fill="none" stroke="black" points="{MARGIN_LEFT},{MARGIN_TOP} {MARGIN_LEFT},{:.1} {:.1},{:.1}"/>"#,
+            axes.bottom(),
+            axes.right(),
+            axes.bottom()
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            (MARGIN_LEFT + axes.right()) / 2.0,
+            HEIGHT - 15.0,
+            escape(self.x_label)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="18" y="{y:.1}" text-anchor="middle" transform="rotate(-90 18 {y:.1})">{}</text>"#,
+            escape(self.y_label),
+            y = (MARGIN_TOP + axes.bottom()) / 2.0,
+        )
+        .unwrap();
+
+        for (i, series) in self.series.iter().enumerate() {
+            let colour = PALETTE[i % PALETTE.len()];
+            let x = axes.right() + 20.0;
+            let y = MARGIN_TOP + 10.0 + i as f64 * 22.0;
+            writeln!(
+                svg,
+                r#"<rect x="{x:.1}" y="{:.1}" width="14" height="14" fill="{colour}"/>"#,
+                y - 11.0
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{y:.1}">{}</text>"#,
+                x + 20.0,
+                escape(&series.label)
+            )
+            .unwrap();
+        }
+        svg
+    }
+}
+
+/// Maps values onto the plot area.
+struct Axes {
+    categories: usize,
+    max: f64,
+    step: f64,
+}
+
+impl Axes {
+    fn new(chart: &Chart) -> Self {
+        let highest = chart
+            .series
+            .iter()
+            .flat_map(|s| s.values.iter().copied())
+            .fold(0.0, f64::max);
+        let step = nice_step(highest / 5.0);
+        Self {
+            categories: chart.categories.len().max(1),
+            max: ((highest / step).ceil() * step).max(step),
+            step,
+        }
+    }
+
+    fn right(&self) -> f64 {
+        WIDTH - MARGIN_RIGHT
+    }
+
+    fn bottom(&self) -> f64 {
+        HEIGHT - MARGIN_BOTTOM
+    }
+
+    fn category_width(&self) -> f64 {
+        (self.right() - MARGIN_LEFT) / self.categories as f64
+    }
+
+    fn category_centre(&self, category: usize) -> f64 {
+        MARGIN_LEFT + self.category_width() * (category as f64 + 0.5)
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        self.bottom() - (value / self.max) * (self.bottom() - MARGIN_TOP)
+    }
+
+    fn ticks(&self) -> impl Iterator<Item = f64> + '_ {
+        let count = (self.max / self.step).round() as usize;
+        (0..=count).map(|i| i as f64 * self.step)
+    }
+}
+
+/// Round `raw` up to a "nice" step size (1, 2 or 5 times a power of ten).
+fn nice_step(raw: f64) -> f64 {
+    if raw <= 0.0 || !raw.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let normalised = raw / magnitude;
+    let nice = match normalised {
+        n if n <= 1.0 => 1.0,
+        n if n <= 2.0 => 2.0,
+        n if n <= 5.0 => 5.0,
+        _ => 10.0,
+    };
+    nice * magnitude
+}
+
+fn format_tick(value: f64) -> String {
+    let formatted = format!("{value:.3}");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart<'a>(categories: &'a [String], series: &'a [Series]) -> Chart<'a> {
+        Chart {
+            title: "Title",
+            x_label: "X",
+            y_label: "Y",
+            categories,
+            series,
+        }
+    }
+
+    #[test]
+    fn nice_steps() {
+        assert_eq!(nice_step(0.0), 1.0);
+        assert_eq!(nice_step(0.13), 0.2);
+        assert_eq!(nice_step(3.0), 5.0);
+        assert_eq!(nice_step(7.0), 10.0);
+    }
+
+    #[test]
+    fn svg_contains_every_series() {
+        let categories = vec!["a".to_string(), "b".to_string()];
+        let series = vec![
+            Series {
+                label: "First <1>".into(),
+                values: vec![1.0, 2.0],
+            },
+            Series {
+                label: "Second".into(),
+                values: vec![0.5, 0.0],
+            },
+        ];
+        let chart = chart(&categories, &series);
+        let line = chart.line_svg();
+        assert!(line.starts_with("<svg"));
+        assert!(line.contains("First &lt;1&gt;"));
+        assert_eq!(
+            line.matches("<polyline fill=\"none\" stroke=\"#").count(),
+            2
+        );
+        let bar = chart.bar_svg();
+        assert_eq!(bar.matches("<rect x=").count(), 4 + 2);
+    }
+}
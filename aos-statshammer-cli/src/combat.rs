@@ -0,0 +1,196 @@
+use crate::format::{Formats, Output, text_table};
+use crate::histogram::values_to_show;
+use crate::input::{TargetArgs, load_unit};
+use crate::overrides::Overrides;
+use crate::target_config::TargetConfig;
+use anyhow::Result;
+use aos_statshammer_core::combat::{Combat, Combatant, Strike, combat};
+use aos_statshammer_core::target::Target;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(ValueEnum, Debug, Copy, Clone, Eq, PartialEq)]
+enum Side {
+    Attacker,
+    Defender,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct CombatArgs {
+    /// The attacking unit (the active player's, which fights first by default)
+    #[arg(short, long, value_name = "FILE")]
+    unit: PathBuf,
+
+    // The defender's defensive profile
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// The defending unit, which attacks back
+    #[arg(long, value_name = "FILE")]
+    defender: PathBuf,
+
+    /// The attacking unit's defensive (target) profile, which the defender attacks
+    #[arg(long, value_name = "FILE")]
+    defence: PathBuf,
+
+    /// A side with strike-first (which fights before a side without it)
+    #[arg(long, value_enum, value_name = "SIDE")]
+    strike_first: Vec<Side>,
+
+    /// A side with strike-last (which fights after a side without it)
+    #[arg(long, value_enum, value_name = "SIDE")]
+    strike_last: Vec<Side>,
+
+    // Applied to the attacking unit and the defender's defensive profile
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl CombatArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let (attacker_config, attacker) = load_unit(&self.unit, &self.overrides, formats)?;
+        let target = self.target.load(&self.overrides, formats)?;
+        let (defender_config, defender) =
+            load_unit(&self.defender, &Overrides::default(), formats)?;
+        let defence: Target = formats.read::<TargetConfig>(&self.defence)?.try_into()?;
+
+        let result = combat(
+            Combatant {
+                unit: &attacker,
+                target: &defence,
+                strike: self.strike(Side::Attacker),
+            },
+            Combatant {
+                unit: &defender,
+                target: &target,
+                strike: self.strike(Side::Defender),
+            },
+        );
+        let output = CombatOutput {
+            attacker: attacker_config.name,
+            attacker_models: attacker.models,
+            defender: defender_config.name,
+            defender_models: defender.models,
+            result,
+        };
+        print!("{}", formats.render(&output)?);
+        Ok(())
+    }
+
+    /// A side with both strike-first and strike-last fights as if it had neither.
+    fn strike(&self, side: Side) -> Strike {
+        match (
+            self.strike_first.contains(&side),
+            self.strike_last.contains(&side),
+        ) {
+            (true, false) => Strike::First,
+            (false, true) => Strike::Last,
+            _ => Strike::Normal,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct CombatOutput {
+    attacker: String,
+    attacker_models: u32,
+    defender: String,
+    defender_models: u32,
+    #[serde(flatten)]
+    result: Combat,
+}
+
+fn percent(probability: f64) -> String {
+    format!("{:.1}%", probability * 100.0)
+}
+
+impl Output for CombatOutput {
+    fn text(&self) -> String {
+        let result = &self.result;
+        let first = if result.attacker_first {
+            &self.attacker
+        } else {
+            &self.defender
+        };
+        let mut text = format!("{first} fights first\n\n");
+
+        let headers = ["Unit", "Models", "Average Losses", "Destroyed"].map(String::from);
+        let rows = vec![
+            vec![
+                self.attacker.clone(),
+                self.attacker_models.to_string(),
+                format!("{:.3}", result.attacker_losses.mean()),
+                percent(result.attacker_destroyed),
+            ],
+            vec![
+                self.defender.clone(),
+                self.defender_models.to_string(),
+                format!("{:.3}", result.defender_losses.mean()),
+                percent(result.defender_destroyed),
+            ],
+        ];
+        text.push_str(&text_table(&headers, &rows));
+
+        let headers = [
+            "Losses".to_string(),
+            self.attacker.clone(),
+            self.defender.clone(),
+        ];
+        let shown = |losses| values_to_show(losses).last().copied().unwrap_or(0);
+        let most = shown(&result.attacker_losses).max(shown(&result.defender_losses));
+        let rows: Vec<Vec<String>> = (0..=most)
+            .map(|losses| {
+                vec![
+                    losses.to_string(),
+                    percent(result.attacker_losses.probability(losses)),
+                    percent(result.defender_losses.probability(losses)),
+                ]
+            })
+            .collect();
+        text.push('\n');
+        text.push_str(&text_table(&headers, &rows));
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(extra: &[&str]) -> CombatArgs {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Test {
+            #[command(flatten)]
+            args: CombatArgs,
+        }
+        let args = [
+            "test",
+            "-u",
+            "a",
+            "-s",
+            "4",
+            "--defender",
+            "b",
+            "--defence",
+            "c",
+        ];
+        Test::try_parse_from(args.iter().chain(extra)).unwrap().args
+    }
+
+    #[test]
+    fn strike_first_and_last_cancel_out() {
+        let args = args(&[
+            "--strike-first",
+            "attacker",
+            "--strike-last",
+            "attacker",
+            "--strike-last",
+            "defender",
+        ]);
+        assert_eq!(args.strike(Side::Attacker), Strike::Normal);
+        assert_eq!(args.strike(Side::Defender), Strike::Last);
+    }
+}
@@ -0,0 +1,457 @@
+use crate::format::{Formats, Output, text_table};
+use crate::input::{TargetArgs, load_weapons};
+use crate::overrides::Overrides;
+use crate::summary;
+use anyhow::{Result, bail};
+use aos_statshammer_core::distribution::Summary;
+use aos_statshammer_core::target::{Save, Target};
+use aos_statshammer_core::validate;
+use aos_statshammer_core::weapon::Weapon;
+use clap::Args;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+/// Differences in damage smaller than this are a tie.
+const TIE: f64 = 1e-9;
+
+/// The extra rend to give both weapons, with `--break-even`.
+const EXTRA_REND: [i16; 2] = [1, 2];
+
+#[derive(Args, Debug)]
+pub(crate) struct CompareArgs {
+    /// The weapon files to compare (the first is the baseline)
+    #[arg(value_name = "WEAPON", num_args = 2.., required = true)]
+    weapons: Vec<PathBuf>,
+
+    // With `--break-even`, only the target's modifiers are used, as every save is compared
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// Compare two weapons against every save, with and without a ward, with extra rend
+    /// and against ethereal targets, and show where each one is better
+    #[arg(long)]
+    break_even: bool,
+
+    /// The ward to compare against, with `--break-even`
+    #[arg(
+        long,
+        value_name = "WARD",
+        default_value_t = 5,
+        value_parser = parse_ward,
+        requires = "break_even"
+    )]
+    ward: u8,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl CompareArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let weapons = load_weapons(&self.weapons, &self.overrides, formats)?;
+        if self.break_even {
+            let [a, b] = weapons.as_slice() else {
+                bail!("--break-even compares exactly two weapons");
+            };
+            let target = self
+                .target
+                .load_or(Target::from(Save::NONE), &self.overrides, formats)?;
+            let output = BreakEvenOutput::new(a, b, &target, self.ward);
+            print!("{}", formats.render(&output)?);
+            return Ok(());
+        }
+        let target = self.target.load(&self.overrides, formats)?;
+        let baseline = weapons[0].1.average_damage(&target);
+        let output = CompareOutput {
+            weapons: weapons
+                .iter()
+                .map(|(name, weapon)| {
+                    let average_damage = weapon.average_damage(&target);
+                    CompareRow {
+                        name: name.clone(),
+                        average_damage,
+                        difference: percentage_difference(average_damage, baseline),
+                        summary: weapon.damage_summary(&target),
+                    }
+                })
+                .collect(),
+        };
+        print!("{}", formats.render(&output)?);
+        Ok(())
+    }
+}
+
+fn parse_ward(value: &str) -> Result<u8> {
+    let ward = value.parse()?;
+    match validate::ward_problem("the ward", ward) {
+        Some(problem) => bail!(problem),
+        None => Ok(ward),
+    }
+}
+
+/// The difference between `value` and `baseline`, as a percentage of `baseline`
+/// (`None` if the baseline is zero).
+pub(crate) fn percentage_difference(value: f64, baseline: f64) -> Option<f64> {
+    (baseline != 0.0).then(|| (value - baseline) / baseline * 100.0)
+}
+
+#[derive(Serialize, Debug)]
+struct CompareOutput {
+    weapons: Vec<CompareRow>,
+}
+
+#[derive(Serialize, Debug)]
+struct CompareRow {
+    name: String,
+    average_damage: f64,
+    /// The percentage difference from the first weapon
+    difference: Option<f64>,
+    /// `None` if the weapon can do too much damage to calculate it quickly
+    summary: Option<Summary>,
+}
+
+impl Output for CompareOutput {
+    fn text(&self) -> String {
+        let headers: Vec<String> = ["Weapon", "Average Damage", "Difference"]
+            .map(String::from)
+            .into_iter()
+            .chain(summary::headers())
+            .collect();
+        let rows: Vec<Vec<String>> = self
+            .weapons
+            .iter()
+            .map(|row| {
+                let mut cells = vec![
+                    row.name.clone(),
+                    format!("{:.3}", row.average_damage),
+                    row.difference
+                        .map_or("-".to_string(), |d| format!("{d:+.1}%")),
+                ];
+                cells.extend(summary::cells(row.summary.as_ref()));
+                cells
+            })
+            .collect();
+        text_table(&headers, &rows)
+    }
+}
+
+/// Where each of two weapons does more damage, against every save.
+#[derive(Serialize, Debug)]
+struct BreakEvenOutput {
+    a: String,
+    b: String,
+    conditions: Vec<Condition>,
+}
+
+/// The comparison against every save, for one kind of target.
+#[derive(Serialize, Debug)]
+struct Condition {
+    name: String,
+    saves: Vec<BreakEvenRow>,
+    /// Where the weapons break even, e.g. "B only beats A against 3+ saves or better".
+    summary: String,
+}
+
+#[derive(Serialize, Debug)]
+struct BreakEvenRow {
+    save: String,
+    a: f64,
+    b: f64,
+    /// The percentage difference of `b` from `a`.
+    difference: Option<f64>,
+    /// The name of the weapon which does more damage (if they aren't equal).
+    better: Option<String>,
+}
+
+impl BreakEvenOutput {
+    fn new(a: &(String, Weapon), b: &(String, Weapon), target: &Target, ward: u8) -> Self {
+        let mut conditions = vec![("Against each save".to_string(), target.clone(), 0)];
+        if target.ward.is_none() {
+            let target = Target {
+                ward: Some(ward),
+                ..target.clone()
+            };
+            conditions.push((format!("With a {ward}+ ward"), target, 0));
+        }
+        if !target.ethereal {
+            // e.g. from a spell or an ability which improves the rend of every weapon
+            for rend in EXTRA_REND {
+                conditions.push((format!("With {rend:+} rend"), target.clone(), rend));
+            }
+            let target = Target {
+                ethereal: true,
+                ..target.clone()
+            };
+            conditions.push(("Ethereal (ignoring rend)".to_string(), target, 0));
+        }
+        let with_rend = |(name, weapon): &(String, Weapon), rend: i16| {
+            let mut weapon = weapon.clone();
+            weapon.rend = weapon.rend + rend;
+            (name.clone(), weapon)
+        };
+        Self {
+            a: a.0.clone(),
+            b: b.0.clone(),
+            conditions: conditions
+                .into_iter()
+                .map(|(name, target, rend)| {
+                    Condition::new(name, &with_rend(a, rend), &with_rend(b, rend), &target)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Condition {
+    fn new(name: String, a: &(String, Weapon), b: &(String, Weapon), target: &Target) -> Self {
+        let damage: Vec<(u8, f64, f64)> = Save::ALL
+            .iter()
+            .map(|&save| {
+                let target = target.with_save(save);
+                (
+                    save,
+                    a.1.average_damage(&target),
+                    b.1.average_damage(&target),
+                )
+            })
+            .collect();
+        let winners: Vec<(u8, Ordering)> = damage
+            .iter()
+            .map(|&(save, a, b)| (save, compare_damage(b, a)))
+            .collect();
+        Self {
+            name,
+            saves: damage
+                .iter()
+                .zip(&winners)
+                .map(|(&(save, a_damage, b_damage), &(_, winner))| BreakEvenRow {
+                    save: Save::from(save).to_string(),
+                    a: a_damage,
+                    b: b_damage,
+                    difference: percentage_difference(b_damage, a_damage),
+                    better: match winner {
+                        Ordering::Greater => Some(b.0.clone()),
+                        Ordering::Less => Some(a.0.clone()),
+                        Ordering::Equal => None,
+                    },
+                })
+                .collect(),
+            summary: summary(&a.0, &b.0, &winners),
+        }
+    }
+}
+
+fn compare_damage(x: f64, y: f64) -> Ordering {
+    if (x - y).abs() < TIE {
+        Ordering::Equal
+    } else {
+        x.total_cmp(&y)
+    }
+}
+
+/// Describe where each weapon is better, from how `b` compares to `a` against each save.
+fn summary(a: &str, b: &str, winners: &[(u8, Ordering)]) -> String {
+    let saves = |ordering| -> Vec<u8> {
+        winners
+            .iter()
+            .filter(|(_, o)| *o == ordering)
+            .map(|(save, _)| *save)
+            .collect()
+    };
+    let (b_wins, a_wins) = (saves(Ordering::Greater), saves(Ordering::Less));
+    let every = b_wins.len() == winners.len() || a_wins.len() == winners.len();
+    match (a_wins.is_empty(), b_wins.is_empty()) {
+        (true, true) => format!("{a} and {b} do the same damage against every save"),
+        _ if every && a_wins.is_empty() => format!("{b} beats {a} against every save"),
+        _ if every => format!("{a} beats {b} against every save"),
+        (true, false) => format!("{a} never beats {b}"),
+        (false, true) => format!("{b} never beats {a}"),
+        // Describe whichever is better against fewer saves
+        _ if b_wins.len() <= a_wins.len() => {
+            format!("{b} only beats {a} against {}", ranges(&b_wins))
+        }
+        _ => format!("{a} only beats {b} against {}", ranges(&a_wins)),
+    }
+}
+
+/// Describe a set of saves (in order), e.g. "3+ saves or better and 6+ saves".
+fn ranges(saves: &[u8]) -> String {
+    let (first, last) = (Save::ALL[0], Save::NONE);
+    let mut runs: Vec<(u8, u8)> = vec![];
+    for &save in saves {
+        match runs.last_mut() {
+            Some((_, end)) if *end + 1 == save => *end = save,
+            _ => runs.push((save, save)),
+        }
+    }
+    runs.iter()
+        .map(|&(start, end)| match (start, end) {
+            (start, end) if start == end && start == last => "targets with no save".to_string(),
+            (start, end) if start == end => format!("{start}+ saves"),
+            (start, end) if start == first && end != last => format!("{end}+ saves or better"),
+            (start, end) if end == last => format!("{start}+ saves or worse"),
+            (start, end) => format!("{start}+ to {end}+ saves"),
+        })
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+impl Output for BreakEvenOutput {
+    fn text(&self) -> String {
+        let headers = ["Save", &self.a, &self.b, "Difference", "Better"].map(String::from);
+        let mut text = String::new();
+        for condition in &self.conditions {
+            let rows: Vec<Vec<String>> = condition
+                .saves
+                .iter()
+                .map(|row| {
+                    vec![
+                        row.save.clone(),
+                        format!("{:.3}", row.a),
+                        format!("{:.3}", row.b),
+                        row.difference
+                            .map_or("-".to_string(), |d| format!("{d:+.1}%")),
+                        row.better.clone().unwrap_or("-".to_string()),
+                    ]
+                })
+                .collect();
+            text.push_str(&format!("{}:\n", condition.name));
+            text.push_str(&text_table(&headers, &rows));
+            text.push_str(&format!("{}\n\n", condition.summary));
+        }
+        text.truncate(text.trim_end().len());
+        text.push('\n');
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::weapon::WeaponBuilder;
+
+    fn weapon(attacks: u8, rend: u8) -> (String, Weapon) {
+        let weapon = WeaponBuilder::default()
+            .attacks(attacks)
+            .hit(3)
+            .wound(3)
+            .rend(rend)
+            .damage(1)
+            .build()
+            .unwrap();
+        (format!("{attacks}/{rend}"), weapon)
+    }
+
+    #[test]
+    fn summaries() {
+        use Ordering::*;
+        let winners = |orderings: [Ordering; 6]| -> Vec<(u8, Ordering)> {
+            Save::ALL.into_iter().zip(orderings).collect()
+        };
+        assert_eq!(
+            summary(
+                "A",
+                "B",
+                &winners([Greater, Greater, Less, Less, Less, Less])
+            ),
+            "B only beats A against 3+ saves or better"
+        );
+        assert_eq!(
+            summary(
+                "A",
+                "B",
+                &winners([Greater, Greater, Greater, Less, Less, Equal])
+            ),
+            "A only beats B against 5+ to 6+ saves"
+        );
+        assert_eq!(
+            summary("A", "B", &winners([Less; 6])),
+            "A beats B against every save"
+        );
+        assert_eq!(
+            summary("A", "B", &winners([Equal, Equal, Less, Less, Less, Less])),
+            "B never beats A"
+        );
+        assert_eq!(
+            ranges(&[2, 4, 6, 7]),
+            "2+ saves and 4+ saves and 6+ saves or worse"
+        );
+        assert_eq!(ranges(&[7]), "targets with no save");
+    }
+
+    #[test]
+    fn rend_breaks_even() {
+        // More attacks against poor saves, against rend against good ones
+        let (many, rending) = (weapon(4, 0), weapon(3, 2));
+        let output = BreakEvenOutput::new(&many, &rending, &Target::from(Save::NONE), 5);
+        let names: Vec<&str> = output.conditions.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Against each save",
+                "With a 5+ ward",
+                "With +1 rend",
+                "With +2 rend",
+                "Ethereal (ignoring rend)"
+            ]
+        );
+        assert_eq!(
+            output.conditions[0].summary,
+            "4/0 only beats 3/2 against 6+ saves or worse"
+        );
+        // More rend on both makes every save worse, so the break-even point moves to better saves
+        assert_eq!(
+            output.conditions[2].summary,
+            "3/2 only beats 4/0 against 4+ saves or better"
+        );
+        assert_eq!(
+            output.conditions[3].summary,
+            "3/2 only beats 4/0 against 3+ saves or better"
+        );
+        // Without rend, the extra attack always wins
+        assert_eq!(
+            output.conditions[4].summary,
+            "4/0 beats 3/2 against every save"
+        );
+
+        // Rend does nothing against ethereal targets, so there's no rend to add
+        let ethereal = Target {
+            ethereal: true,
+            ..Target::from(Save::NONE)
+        };
+        let output = BreakEvenOutput::new(&many, &rending, &ethereal, 5);
+        assert_eq!(output.conditions.len(), 2);
+    }
+
+    #[test]
+    fn wards_must_be_possible_to_fail() {
+        use clap::Parser;
+
+        #[derive(Parser, Debug)]
+        struct Test {
+            #[command(flatten)]
+            compare: CompareArgs,
+        }
+        let parse = |ward: &str| {
+            Test::try_parse_from(["test", "a", "b", "--break-even", "--ward", ward])
+                .map(|test| test.compare.ward)
+        };
+        assert_eq!(parse("4").unwrap(), 4);
+        for ward in ["0", "1", "7"] {
+            let err = parse(ward).unwrap_err().to_string();
+            assert!(
+                err.contains(&format!("the ward must be between 2 and 6 (got {ward})")),
+                "{err}"
+            );
+        }
+        assert!(parse("x").is_err());
+    }
+
+    #[test]
+    fn percentage_differences() {
+        assert_eq!(percentage_difference(1.5, 1.0), Some(50.0));
+        assert_eq!(percentage_difference(0.5, 1.0), Some(-50.0));
+        assert_eq!(percentage_difference(1.0, 0.0), None);
+    }
+}
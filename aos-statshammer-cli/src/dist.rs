@@ -0,0 +1,145 @@
+use crate::format::{Formats, Output, text_table};
+use crate::histogram;
+use crate::input::{TargetArgs, load_weapon};
+use crate::overrides::Overrides;
+use crate::summary;
+use anyhow::Result;
+use aos_statshammer_core::distribution::Distribution;
+use aos_statshammer_core::probability::Exact;
+use aos_statshammer_core::report::DistributionReport;
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub(crate) struct DistArgs {
+    #[arg(short, long, value_name = "FILE")]
+    weapon: PathBuf,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// The maximum width of the histogram (in columns)
+    #[arg(long, value_name = "COLUMNS", default_value_t = 80)]
+    width: usize,
+
+    /// Show the exact probabilities as fractions, instead of the histogram
+    #[arg(long)]
+    exact: bool,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl DistArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let weapon = load_weapon(&self.weapon, &self.overrides, formats)?;
+        let target = self.target.load(&self.overrides, formats)?;
+        if self.exact {
+            let output = ExactOutput::new(&weapon.damage_distribution_in(&target));
+            print!("{}", formats.render(&output)?);
+        } else {
+            let output = DistributionOutput::new(weapon.damage_distribution(&target), self.width);
+            print!("{}", formats.render(&output)?);
+        }
+        Ok(())
+    }
+}
+
+/// The damage distribution, shown as a histogram in text mode.
+#[derive(Serialize, Debug)]
+pub(crate) struct DistributionOutput {
+    #[serde(flatten)]
+    report: DistributionReport,
+    #[serde(skip)]
+    distribution: Distribution,
+    #[serde(skip)]
+    width: usize,
+}
+
+impl DistributionOutput {
+    pub fn new(distribution: Distribution, width: usize) -> Self {
+        Self {
+            report: DistributionReport::from(&distribution),
+            distribution,
+            width,
+        }
+    }
+
+    /// The histogram on its own, without the statistics.
+    pub fn histogram(&self) -> String {
+        histogram::render(&self.distribution, self.width)
+    }
+}
+
+impl Output for DistributionOutput {
+    fn text(&self) -> String {
+        let mut text = self.histogram();
+        text.push('\n');
+        text.push_str(&summary::text(Some(&self.report.summary)));
+        text
+    }
+}
+
+/// The damage distribution as exact fractions (e.g. `"4/3"`), for publishing odds.
+#[derive(Serialize, Debug)]
+struct ExactOutput {
+    mean: String,
+    /// `P(damage = k)`, indexed by `k`
+    probability: Vec<String>,
+    /// `P(damage >= k)`, indexed by `k`
+    at_least: Vec<String>,
+}
+
+impl ExactOutput {
+    fn new(distribution: &Distribution<Exact>) -> Self {
+        Self {
+            mean: distribution.mean().to_string(),
+            probability: distribution.pmf().iter().map(Exact::to_string).collect(),
+            at_least: (0..=distribution.max())
+                .map(|k| distribution.at_least(k).to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Output for ExactOutput {
+    fn text(&self) -> String {
+        let headers = ["Damage", "Probability", "At Least"].map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .probability
+            .iter()
+            .zip(&self.at_least)
+            .enumerate()
+            .map(|(k, (p, at_least))| vec![k.to_string(), p.clone(), at_least.clone()])
+            .collect();
+        format!(
+            "Average Damage: {}\n\n{}",
+            self.mean,
+            text_table(&headers, &rows)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::target::Target;
+    use aos_statshammer_core::weapon::WeaponBuilder;
+
+    #[test]
+    fn exact_fractions() {
+        let weapon = WeaponBuilder::default()
+            .attacks(1)
+            .hit(3)
+            .wound(4)
+            .rend(0)
+            .damage(1)
+            .build()
+            .unwrap();
+        let output = ExactOutput::new(&weapon.damage_distribution_in(&Target::from(4)));
+        assert_eq!(output.mean, "1/6");
+        assert_eq!(output.probability, ["5/6", "1/6"]);
+        assert_eq!(output.at_least, ["1", "1/6"]);
+    }
+}
@@ -0,0 +1,319 @@
+use crate::format::{Formats, Output, text_table};
+use crate::input::{label, load_unit};
+use crate::overrides::Overrides;
+use crate::target_config::TargetConfig;
+use anyhow::{Result, bail};
+use aos_statshammer_core::target::{Save, Target};
+use aos_statshammer_core::unit::Unit;
+use clap::{ArgGroup, Args, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(ValueEnum, Debug, Copy, Clone, Eq, PartialEq)]
+enum Metric {
+    /// Damage per 100 points
+    Damage,
+    /// Models slain per 100 points
+    Slain,
+    /// Points killed per 100 points (every target needs points)
+    PointsKilled,
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("targets").required(true).multiple(true).args(["target", "save"])))]
+pub(crate) struct EfficiencyArgs {
+    /// The unit files to compare
+    #[arg(short, long, value_name = "FILE", required = true)]
+    unit: Vec<PathBuf>,
+
+    /// The target files to attack
+    #[arg(short, long, value_name = "FILE")]
+    target: Vec<PathBuf>,
+
+    /// Saves to attack (as single model targets), as well as any target files
+    #[arg(short, long)]
+    save: Vec<u8>,
+
+    /// Rank the units by a metric, averaged across every target
+    #[arg(long, value_enum, value_name = "METRIC")]
+    rank: Option<Metric>,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl EfficiencyArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let units = self
+            .unit
+            .iter()
+            .map(|path| {
+                let (config, unit) = load_unit(path, &self.overrides, formats)?;
+                Ok((config.name, config.points, unit))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut targets = vec![];
+        for path in &self.target {
+            let config: TargetConfig = formats.read(path)?;
+            targets.push((label(path), config));
+        }
+        for &save in &self.save {
+            targets.push((Save::from(save).to_string(), TargetConfig::from_save(save)));
+        }
+        let targets = targets
+            .into_iter()
+            .map(|(name, config)| {
+                let points = config.points();
+                let mut config = Some(config);
+                self.overrides.apply_to_target(&mut config)?;
+                let target: Target = config.expect("overrides keep the target").try_into()?;
+                Ok((name, points, target))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let rows: Vec<EfficiencyRow> = units
+            .iter()
+            .flat_map(|(name, points, unit)| {
+                targets
+                    .iter()
+                    .map(|(target_name, target_points, target)| {
+                        EfficiencyRow::new(name, *points, unit, target_name, *target_points, target)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        match self.rank {
+            Some(metric) => {
+                let output = RankOutput::new(metric, &rows, targets.len())?;
+                print!("{}", formats.render(&output)?);
+            }
+            None => print!("{}", formats.render(&EfficiencyOutput { rows })?),
+        }
+        Ok(())
+    }
+}
+
+/// The efficiency of a unit against a single target.
+#[derive(Serialize, Debug)]
+struct EfficiencyRow {
+    unit: String,
+    target: String,
+    points: Option<u32>,
+    average_damage: f64,
+    damage_per_100_points: Option<f64>,
+    models_slain: f64,
+    models_slain_per_100_points: Option<f64>,
+    /// The points value of the target models slain (if the target has points).
+    points_killed: Option<f64>,
+}
+
+impl EfficiencyRow {
+    fn new(
+        name: &str,
+        points: Option<u32>,
+        unit: &Unit,
+        target_name: &str,
+        target_points: Option<u32>,
+        target: &Target,
+    ) -> Self {
+        let damage = unit.damage_distribution(target);
+        let average_damage = damage.mean();
+        let models_slain = target.models_slain(&damage).mean();
+        let per_100 = |value: f64| points.filter(|p| *p > 0).map(|p| value * 100.0 / p as f64);
+        Self {
+            unit: name.to_string(),
+            target: target_name.to_string(),
+            points,
+            average_damage,
+            damage_per_100_points: per_100(average_damage),
+            models_slain,
+            models_slain_per_100_points: per_100(models_slain),
+            points_killed: target_points
+                .map(|p| p as f64 * models_slain / target.models.max(1) as f64),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct EfficiencyOutput {
+    rows: Vec<EfficiencyRow>,
+}
+
+fn optional(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{v:.3}"))
+}
+
+impl Output for EfficiencyOutput {
+    fn text(&self) -> String {
+        let headers = [
+            "Unit",
+            "Target",
+            "Points",
+            "Damage",
+            "Damage/100pts",
+            "Slain",
+            "Slain/100pts",
+            "Points killed",
+        ]
+        .map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                vec![
+                    row.unit.clone(),
+                    row.target.clone(),
+                    row.points.map_or("-".to_string(), |p| p.to_string()),
+                    format!("{:.3}", row.average_damage),
+                    optional(row.damage_per_100_points),
+                    format!("{:.3}", row.models_slain),
+                    optional(row.models_slain_per_100_points),
+                    optional(row.points_killed),
+                ]
+            })
+            .collect();
+        text_table(&headers, &rows)
+    }
+}
+
+/// The units sorted by a metric, averaged across every target.
+#[derive(Serialize, Debug)]
+struct RankOutput {
+    metric: String,
+    units: Vec<RankRow>,
+}
+
+#[derive(Serialize, Debug)]
+struct RankRow {
+    rank: usize,
+    unit: String,
+    points: u32,
+    value: f64,
+}
+
+impl RankOutput {
+    /// `rows` has a row for every target, for each unit in turn.
+    fn new(metric: Metric, rows: &[EfficiencyRow], targets: usize) -> Result<Self> {
+        let mut units = rows
+            .chunks(targets.max(1))
+            .map(|rows| {
+                let unit = &rows[0];
+                let Some(points) = unit.points.filter(|p| *p > 0) else {
+                    bail!("Unable to rank `{}`, as it has no points", unit.unit);
+                };
+                let mut total = 0.0;
+                for row in rows {
+                    total += match metric {
+                        Metric::Damage => row.average_damage,
+                        Metric::Slain => row.models_slain,
+                        Metric::PointsKilled => match row.points_killed {
+                            Some(killed) => killed,
+                            None => bail!(
+                                "Unable to rank by points killed, as `{}` has no points",
+                                row.target
+                            ),
+                        },
+                    };
+                }
+                Ok(RankRow {
+                    rank: 0,
+                    unit: unit.unit.clone(),
+                    points,
+                    value: total / rows.len() as f64 * 100.0 / points as f64,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        units.sort_by(|a, b| b.value.total_cmp(&a.value));
+        for (i, unit) in units.iter_mut().enumerate() {
+            unit.rank = i + 1;
+        }
+        let metric = match metric {
+            Metric::Damage => "Damage/100pts",
+            Metric::Slain => "Slain/100pts",
+            Metric::PointsKilled => "Points killed/100pts",
+        };
+        Ok(Self {
+            metric: metric.to_string(),
+            units,
+        })
+    }
+}
+
+impl Output for RankOutput {
+    fn text(&self) -> String {
+        let headers = ["Rank", "Unit", "Points", &self.metric].map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .units
+            .iter()
+            .map(|u| {
+                vec![
+                    u.rank.to_string(),
+                    u.unit.clone(),
+                    u.points.to_string(),
+                    format!("{:.3}", u.value),
+                ]
+            })
+            .collect();
+        text_table(&headers, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::target::TargetBuilder;
+    use aos_statshammer_core::unit::UnitWeapon;
+    use aos_statshammer_core::weapon::WeaponBuilder;
+
+    fn unit(attacks: u8) -> Unit {
+        let weapon = WeaponBuilder::default()
+            .attacks(attacks)
+            .hit(3)
+            .wound(3)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap();
+        Unit {
+            models: 5,
+            weapons: vec![UnitWeapon { weapon, models: 5 }],
+        }
+    }
+
+    fn target() -> Target {
+        TargetBuilder::default()
+            .save(4)
+            .health(2)
+            .models(10)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn per_100_points() {
+        let row = EfficiencyRow::new("a", Some(200), &unit(2), "t", Some(100), &target());
+        let close = |a: Option<f64>, b: f64| (a.unwrap() - b).abs() < 1e-9;
+        assert!(close(row.damage_per_100_points, row.average_damage / 2.0));
+        assert!(close(row.points_killed, row.models_slain * 10.0));
+        let row = EfficiencyRow::new("a", None, &unit(2), "t", None, &target());
+        assert_eq!((row.damage_per_100_points, row.points_killed), (None, None));
+    }
+
+    #[test]
+    fn rank_sorts_by_metric() {
+        let rows = vec![
+            EfficiencyRow::new("cheap", Some(100), &unit(2), "t", None, &target()),
+            EfficiencyRow::new("pricey", Some(300), &unit(4), "t", None, &target()),
+        ];
+        let output = RankOutput::new(Metric::Damage, &rows, 1).unwrap();
+        let ranked: Vec<_> = output
+            .units
+            .iter()
+            .map(|u| (u.rank, u.unit.as_str()))
+            .collect();
+        assert_eq!(ranked, [(1, "cheap"), (2, "pricey")]);
+        assert!(RankOutput::new(Metric::PointsKilled, &rows, 1).is_err());
+    }
+}
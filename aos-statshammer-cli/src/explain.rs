@@ -0,0 +1,345 @@
+use crate::format::{Formats, Output};
+use crate::input::{TargetArgs, load_weapon_config};
+use crate::overrides::Overrides;
+use crate::weapon_config::{RollCharacteristic, ValueCharacteristic, WeaponConfig, reroll_name};
+use anyhow::Result;
+use aos_statshammer_core::target::Target;
+use aos_statshammer_core::weapon::Weapon;
+use aos_statshammer_core::{Characteristic, Exploding, Reroll};
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub(crate) struct ExplainArgs {
+    #[arg(short, long, value_name = "FILE")]
+    weapon: PathBuf,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl ExplainArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let weapon = load_weapon_config(&self.weapon, &self.overrides, formats)?;
+        let target = self.target.load(&self.overrides, formats)?;
+        print!("{}", formats.render(&explain(&weapon, &target)?)?);
+        Ok(())
+    }
+}
+
+/// A single stage of the calculation.
+#[derive(Serialize, Debug)]
+struct Step {
+    stage: &'static str,
+    /// The characteristic used in this stage, after any modifiers
+    characteristic: String,
+    /// The notes explaining how the characteristic was reached, and which rule
+    /// caused each modifier
+    notes: Vec<String>,
+    /// How the average was calculated from the previous stage
+    formula: String,
+    /// The average number of attacks/hits/wounds/damage after this stage
+    average: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct ExplainOutput {
+    steps: Vec<Step>,
+    average_damage: f64,
+}
+
+/// Walk through the average damage calculation, one stage at a time. The config is
+/// used (rather than the [`Weapon`]) to name the rule behind each modifier.
+pub(crate) fn explain(config: &WeaponConfig, target: &Target) -> Result<ExplainOutput> {
+    let weapon: Weapon = config.clone().try_into()?;
+    let mut steps = vec![];
+    let breakdown = weapon.breakdown(target);
+
+    let attacks = weapon.attacks.modified();
+    steps.push(Step {
+        stage: "Attacks",
+        characteristic: attacks.to_string(),
+        notes: modifier_note(
+            weapon.attacks.unmodified().to_string(),
+            &config.bonus_sources(ValueCharacteristic::Attacks),
+            attacks.to_string(),
+        ),
+        formula: format!("{attacks} attacks"),
+        average: breakdown.attacks,
+    });
+
+    let (notes, formula) = roll(
+        config,
+        RollCharacteristic::Hit,
+        &weapon.hit,
+        ("attacks", "attack", breakdown.attacks),
+        ("hits", breakdown.hit_rolls, breakdown.hits),
+    );
+    steps.push(Step {
+        stage: "Hit",
+        characteristic: format!("{}+", weapon.hit.modified()),
+        notes,
+        formula,
+        average: breakdown.hits,
+    });
+
+    let (notes, formula) = roll(
+        config,
+        RollCharacteristic::Wound,
+        &weapon.wound,
+        ("hits", "hit", breakdown.hits),
+        ("wounds", breakdown.wound_rolls, breakdown.wounds),
+    );
+    steps.push(Step {
+        stage: "Wound",
+        characteristic: format!("{}+", weapon.wound.modified()),
+        notes,
+        formula,
+        average: breakdown.wounds,
+    });
+
+    let rend = weapon.rend.modified();
+    let save_target = target.save_target(rend);
+    let unsaved = target.average_unsaved(rend);
+    let mut notes = modifier_note(
+        format!("Rend {}", weapon.rend.unmodified()),
+        &config.bonus_sources(ValueCharacteristic::Rend),
+        rend.to_string(),
+    );
+    let mut modifiers = vec![];
+    if rend > 0 {
+        modifiers.push(format!("rend {rend}"));
+    }
+    if target.save.bonus() != 0 {
+        modifiers.push(format!("a {:+} modifier", target.save.bonus()));
+    }
+    let save = save_name(target.save.unmodified());
+    notes.push(if modifiers.is_empty() {
+        format!("{save}, P(unsaved) = {unsaved:.3}")
+    } else {
+        format!(
+            "{save} with {} → {}, P(unsaved) = {unsaved:.3}",
+            modifiers.join(" and "),
+            save_name(save_target)
+        )
+    });
+    if target.ethereal {
+        notes.push("Ethereal, so the save is not modified".to_string());
+    }
+    if let Some(reroll) = target.save.reroll {
+        notes.push(format!(
+            "Reroll {} saves by the target",
+            reroll_name(reroll)
+        ));
+    }
+    steps.push(Step {
+        stage: "Save",
+        characteristic: save_name(save_target),
+        notes,
+        formula: format!(
+            "{:.3} wounds × {unsaved:.3} = {:.3} unsaved wounds",
+            breakdown.wounds, breakdown.unsaved
+        ),
+        average: breakdown.unsaved,
+    });
+
+    let damage = weapon.damage.modified();
+    steps.push(Step {
+        stage: "Damage",
+        characteristic: damage.to_string(),
+        notes: modifier_note(
+            weapon.damage.unmodified().to_string(),
+            &config.bonus_sources(ValueCharacteristic::Damage),
+            damage.to_string(),
+        ),
+        formula: format!(
+            "{:.3} unsaved wounds × {damage} damage = {:.3} damage",
+            breakdown.unsaved, breakdown.damage
+        ),
+        average: breakdown.damage,
+    });
+
+    if let Some(ward) = target.ward {
+        let unwarded = target.average_unwarded();
+        steps.push(Step {
+            stage: "Ward",
+            characteristic: format!("{ward}+"),
+            notes: vec![format!(
+                "P(unwarded) = {unwarded:.3}, {:.3} damage negated",
+                breakdown.warded
+            )],
+            formula: format!(
+                "{:.3} damage × {unwarded:.3} = {:.3} damage",
+                breakdown.damage, breakdown.final_damage
+            ),
+            average: breakdown.final_damage,
+        });
+    }
+
+    Ok(ExplainOutput {
+        steps,
+        average_damage: breakdown.final_damage,
+    })
+}
+
+/// The notes and formula for rolling `characteristic` once for each of the previous
+/// stage's results (`(plural, singular, average)`), giving `(successes, rolls, average)`.
+fn roll<C: Reroll + Exploding>(
+    config: &WeaponConfig,
+    to: RollCharacteristic,
+    characteristic: &C,
+    (previous, each, average): (&str, &str, f64),
+    (successes, rolls, successful): (&str, f64, f64),
+) -> (Vec<String>, String) {
+    let value = match to {
+        RollCharacteristic::Hit => ValueCharacteristic::Hit,
+        RollCharacteristic::Wound => ValueCharacteristic::Wound,
+    };
+    let success = characteristic.success_probability();
+    let mut notes = vec![format!(
+        "{}, P = {success:.3}",
+        with_sources(
+            format!("{}+", characteristic.unmodified()),
+            &config.bonus_sources(value),
+            format!("{}+", characteristic.modified()),
+        )
+    )];
+    if let (Some(reroll), Some(name)) = (characteristic.reroll_type(), config.reroll_name(to)) {
+        notes.push(format!(
+            "Reroll {} from '{name}' adds {:.3} rolls/{each}",
+            reroll_name(reroll),
+            characteristic.reroll_probability()
+        ));
+    }
+    if let (Some(ab), Some(on), Some(name)) = (
+        characteristic.exploding_ability(),
+        characteristic.exploding_target(),
+        config.exploding_name(to),
+    ) {
+        // The trigger is modified along with the roll, unless it is on an unmodified roll
+        let trigger = match ab.on == on {
+            true => format!("{on}+"),
+            false => format!("{}+ → {on}+", ab.on),
+        };
+        notes.push(format!(
+            "Exploding {trigger} from '{name}' ({} extra per trigger) adds {:.3} rolls/{each}",
+            ab.extra,
+            characteristic.extra_probability()
+        ));
+    }
+    let per = if average > 0.0 { rolls / average } else { 1.0 };
+    let formula = format!(
+        "{average:.3} {previous} × {per:.3} rolls/{each} × {success:.3} = {successful:.3} {successes}"
+    );
+    (notes, formula)
+}
+
+/// A note describing how the rules in `sources` modified the characteristic, if at all.
+fn modifier_note(unmodified: String, sources: &[(String, i16)], modified: String) -> Vec<String> {
+    if sources.is_empty() {
+        vec![]
+    } else {
+        vec![with_sources(unmodified, sources, modified)]
+    }
+}
+
+/// e.g. "3+ with +1 from 'All-out Attack' → 2+" (or just "3+" without any modifiers).
+fn with_sources(unmodified: String, sources: &[(String, i16)], modified: String) -> String {
+    if sources.is_empty() {
+        return unmodified;
+    }
+    let sources: Vec<String> = sources
+        .iter()
+        .map(|(name, amount)| format!("{amount:+} from '{name}'"))
+        .collect();
+    format!("{unmodified} with {} → {modified}", sources.join(" and "))
+}
+
+fn save_name(save_target: u8) -> String {
+    if save_target > 6 {
+        "-".to_string()
+    } else {
+        format!("{save_target}+")
+    }
+}
+
+impl ExplainOutput {
+    /// The steps on their own, without the average damage.
+    pub fn steps(&self) -> String {
+        let mut text = String::new();
+        for step in &self.steps {
+            text.push_str(&format!(
+                "{:<8}{:<4}→ {:.3}\n",
+                step.stage, step.characteristic, step.average
+            ));
+            for note in &step.notes {
+                text.push_str(&format!("            {note}\n"));
+            }
+            text.push_str(&format!("            = {}\n", step.formula));
+        }
+        text
+    }
+}
+
+impl Output for ExplainOutput {
+    fn text(&self) -> String {
+        let mut text = self.steps();
+        text.push_str(&format!("Average Damage: {:.3}\n", self.average_damage));
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::target::TargetBuilder;
+
+    fn config() -> WeaponConfig {
+        toml::from_str(
+            r#"
+            [characteristics]
+            attack = 3
+            hit = 3
+            wound = 4
+            rend = 1
+            damage = 2
+
+            [abilities]
+            bonus = [{ to = "hit", name = "All-out Attack" }, { to = "damage" }]
+            reroll = [{ to = "hit", type = "ones" }]
+            exploding = [{ to = "wound" }]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn steps_end_at_the_average() {
+        let weapon: Weapon = config().try_into().unwrap();
+        for target in [
+            Target::from(4),
+            TargetBuilder::default().save(4).ward(5).build().unwrap(),
+        ] {
+            let output = explain(&config(), &target).unwrap();
+            let last = output.steps.last().unwrap().average;
+            assert!((last - weapon.average_damage(&target)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn modifiers_are_named() {
+        let output = explain(&config(), &Target::from(4)).unwrap();
+        let notes = |stage| {
+            let step = output.steps.iter().find(|s| s.stage == stage).unwrap();
+            step.notes.join("\n")
+        };
+        assert!(notes("Hit").contains("3+ with +1 from 'All-out Attack' → 2+, P = 0.833"));
+        assert!(notes("Hit").contains("Reroll ones from 'reroll ones to hit' adds 0.167"));
+        assert!(notes("Wound").contains("from 'exploding 6s to wound'"));
+        assert_eq!(notes("Damage"), "2 with +1 from 'bonus to damage' → 3");
+    }
+}
@@ -0,0 +1,122 @@
+use crate::chart::{Chart, Series};
+use crate::format::Formats;
+use crate::histogram::values_to_show;
+use crate::input::{TargetArgs, load_weapons};
+use crate::overrides::Overrides;
+use anyhow::Result;
+use aos_statshammer_core::distribution::Distribution;
+use aos_statshammer_core::target::{Save, Target};
+use aos_statshammer_core::weapon::Weapon;
+use clap::{Args, ValueEnum};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(ValueEnum, Debug, Copy, Clone, Default)]
+pub(crate) enum ChartKind {
+    /// A line chart of the average damage against each save
+    #[default]
+    Saves,
+    /// A bar chart of the damage distribution against the target
+    Distribution,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ExportChartArgs {
+    /// The weapon files to chart (each one is a separate series)
+    #[arg(short, long, value_name = "FILE", required = true)]
+    weapon: Vec<PathBuf>,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    #[arg(short, long, value_enum, default_value_t)]
+    kind: ChartKind,
+
+    /// Where to write the SVG (defaults to stdout)
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl ExportChartArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let weapons = load_weapons(&self.weapon, &self.overrides, formats)?;
+        let svg = match self.kind {
+            ChartKind::Saves => {
+                // Only the modifiers matter here, so a target is optional
+                let target =
+                    self.target
+                        .load_or(Target::from(Save::NONE), &self.overrides, formats)?;
+                saves_svg(&weapons, &target)
+            }
+            ChartKind::Distribution => {
+                let target = self.target.load(&self.overrides, formats)?;
+                distribution_svg(&weapons, &target)
+            }
+        };
+        match &self.output {
+            Some(path) => fs::write(path, svg)?,
+            None => print!("{svg}"),
+        }
+        Ok(())
+    }
+}
+
+/// A line chart of the average damage of each weapon against every save.
+pub(crate) fn saves_svg(weapons: &[(String, Weapon)], target: &Target) -> String {
+    let categories: Vec<String> = Save::ALL
+        .iter()
+        .map(|s| Save::from(*s).to_string())
+        .collect();
+    let series: Vec<Series> = weapons
+        .iter()
+        .map(|(label, weapon)| Series {
+            label: label.clone(),
+            values: weapon
+                .save_table(target)
+                .into_iter()
+                .map(|(_, damage)| damage)
+                .collect(),
+        })
+        .collect();
+    Chart {
+        title: "Average Damage by Save",
+        x_label: "Save",
+        y_label: "Average Damage",
+        categories: &categories,
+        series: &series,
+    }
+    .line_svg()
+}
+
+/// A bar chart of the damage distribution of each weapon against `target`.
+pub(crate) fn distribution_svg(weapons: &[(String, Weapon)], target: &Target) -> String {
+    let distributions: Vec<Distribution> = weapons
+        .iter()
+        .map(|(_, weapon)| weapon.damage_distribution(target))
+        .collect();
+    let max = distributions
+        .iter()
+        .filter_map(|d| values_to_show(d).last().copied())
+        .max()
+        .unwrap_or(0);
+    let categories: Vec<String> = (0..=max).map(|k| k.to_string()).collect();
+    let series: Vec<Series> = weapons
+        .iter()
+        .zip(&distributions)
+        .map(|((label, _), dist)| Series {
+            label: label.clone(),
+            values: (0..=max).map(|k| dist.probability(k) * 100.0).collect(),
+        })
+        .collect();
+    Chart {
+        title: &format!("Damage Distribution vs {}", target.save),
+        x_label: "Damage",
+        y_label: "Probability (%)",
+        categories: &categories,
+        series: &series,
+    }
+    .bar_svg()
+}
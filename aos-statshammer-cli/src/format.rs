@@ -0,0 +1,163 @@
+use crate::catalog::{self, Catalog};
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(ValueEnum, Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum InputFormat {
+    Toml,
+    Json,
+}
+
+impl InputFormat {
+    /// Guess the format from the file extension, falling back to TOML.
+    fn detect(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub(crate) enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// A result which can be shown in any of the output formats.
+pub(crate) trait Output: Serialize {
+    fn text(&self) -> String;
+}
+
+// The input/output options, shared by every command.
+#[derive(Args, Debug, Clone, Default)]
+pub(crate) struct Formats {
+    /// The format of the input files [default: detected from the file extension]
+    #[arg(long, global = true, value_enum)]
+    pub input_format: Option<InputFormat>,
+
+    /// The format of the output
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub output_format: OutputFormat,
+
+    /// The catalog directory, used for `catalog:` references
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        env = "AOS_STATSHAMMER_CATALOG",
+        default_value = "catalog"
+    )]
+    pub catalog: PathBuf,
+}
+
+impl Formats {
+    /// Read and parse a config file, which may be a `catalog:` reference.
+    pub fn read<T: DeserializeOwned>(&self, path: &Path) -> Result<T> {
+        let path = &self.resolve(path)?;
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read {}", path.display()))?;
+        self.parse(path, &contents)
+    }
+
+    /// The file a path refers to, looking up `catalog:` references in the catalog.
+    pub fn resolve(&self, path: &Path) -> Result<PathBuf> {
+        match catalog::reference(path) {
+            Some(reference) => Catalog::new(&self.catalog).resolve(reference),
+            None => Ok(path.to_path_buf()),
+        }
+    }
+
+    /// Parse the contents of a config file (`path` is only used to detect the format).
+    pub fn parse<T: DeserializeOwned>(&self, path: &Path, contents: &str) -> Result<T> {
+        let parsed = match self
+            .input_format
+            .unwrap_or_else(|| InputFormat::detect(path))
+        {
+            InputFormat::Toml => toml::from_str(contents).map_err(anyhow::Error::from),
+            InputFormat::Json => serde_json::from_str(contents).map_err(anyhow::Error::from),
+        };
+        parsed.with_context(|| format!("Unable to parse {}", path.display()))
+    }
+
+    pub fn render<T: Output>(&self, output: &T) -> Result<String> {
+        match self.output_format {
+            OutputFormat::Text => Ok(output.text()),
+            OutputFormat::Json => Ok(serde_json::to_string_pretty(output)? + "\n"),
+        }
+    }
+}
+
+/// Lay out `rows` as a plain text table, with the first column left aligned
+/// and every other column right aligned.
+pub(crate) fn text_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| match i {
+                0 => format!("{cell:<width$}"),
+                _ => format!("{cell:>width$}"),
+            })
+            .collect();
+        cells.join("  ").trim_end().to_string() + "\n"
+    };
+    let mut text = line(headers);
+    text.push_str(&line(
+        &widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>(),
+    ));
+    for row in rows {
+        text.push_str(&line(row));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_input_format() {
+        assert_eq!(InputFormat::detect(Path::new("a.json")), InputFormat::Json);
+        assert_eq!(InputFormat::detect(Path::new("a.toml")), InputFormat::Toml);
+        assert_eq!(InputFormat::detect(Path::new("a")), InputFormat::Toml);
+    }
+
+    #[test]
+    fn text_table_alignment() {
+        let headers = vec!["Name".to_string(), "Value".to_string()];
+        let rows = vec![vec!["a".to_string(), "1.5".to_string()]];
+        assert_eq!(
+            text_table(&headers, &rows),
+            "Name  Value\n----  -----\na       1.5\n"
+        );
+    }
+
+    #[test]
+    fn parse_uses_explicit_format() {
+        #[derive(serde::Deserialize)]
+        struct Example {
+            value: u8,
+        }
+        let formats = Formats {
+            input_format: Some(InputFormat::Json),
+            ..Default::default()
+        };
+        let parsed: Example = formats
+            .parse(Path::new("a.toml"), r#"{"value": 3}"#)
+            .unwrap();
+        assert_eq!(parsed.value, 3);
+    }
+}
@@ -0,0 +1,155 @@
+use aos_statshammer_core::distribution::Distribution;
+use std::fmt::Write;
+
+/// Rows in the tail with a smaller chance than this of being reached are not shown.
+const TAIL_CUTOFF: f64 = 0.001;
+const EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+/// The narrowest bar worth keeping the mean/median markers and the `P(≥k)` column
+/// for, as they are dropped (in that order) when there isn't enough space.
+const MIN_BAR_WIDTH: usize = 8;
+
+/// Render `dist` as a bar chart of `P(damage = k)` (along with `P(damage >= k)`)
+/// which fits within `width` columns.
+pub(crate) fn render(dist: &Distribution, width: usize) -> String {
+    let rows = values_to_show(dist);
+    let mean = dist.mean();
+    let median = dist.median();
+    let mean_row = rows
+        .iter()
+        .copied()
+        .min_by(|a, b| {
+            (*a as f64 - mean)
+                .abs()
+                .total_cmp(&(*b as f64 - mean).abs())
+        })
+        .unwrap_or(0);
+    let marker = |k: usize| match (k == mean_row, k == median) {
+        (true, true) => " ◀ mean, median",
+        (true, false) => " ◀ mean",
+        (false, true) => " ◀ median",
+        (false, false) => "",
+    };
+
+    let full_header = format!("{:>6} │ {:>6} {:>7} │", "Damage", "P(=k)", "P(≥k)");
+    let markers = rows
+        .iter()
+        .map(|&k| marker(k).chars().count())
+        .max()
+        .unwrap_or(0);
+    let fits = |columns: usize| columns + 1 + MIN_BAR_WIDTH <= width;
+    let show_markers = fits(full_header.chars().count() + markers);
+    let show_at_least = fits(full_header.chars().count());
+    let header = if show_at_least {
+        full_header
+    } else {
+        format!("{:>6} │ {:>6} │", "Damage", "P(=k)")
+    };
+    let fixed = header.chars().count() + 1;
+    let markers = if show_markers { markers } else { 0 };
+    let bar_width = width.saturating_sub(fixed + markers).max(1);
+    let highest = rows
+        .iter()
+        .map(|&k| dist.probability(k))
+        .fold(0.0, f64::max);
+
+    let mut output = String::new();
+    writeln!(output, "{header}").unwrap();
+    for &k in &rows {
+        let probability = dist.probability(k);
+        let bar = bar(probability / highest, bar_width);
+        write!(output, "{k:>6} │ {:>5.1}% ", probability * 100.0).unwrap();
+        if show_at_least {
+            write!(output, "{:>6.1}% ", dist.at_least(k) * 100.0).unwrap();
+        }
+        write!(output, "│ {bar}").unwrap();
+        if show_markers {
+            output.push_str(marker(k));
+        }
+        output.push('\n');
+    }
+    if let Some(&last) = rows.last() {
+        let remaining = dist.at_least(last + 1);
+        if remaining > 0.0 {
+            writeln!(
+                output,
+                "{:>6} │ P(damage > {last}) = {:.2}%",
+                "…",
+                remaining * 100.0
+            )
+            .unwrap();
+        }
+    }
+    writeln!(output, "Mean: {mean:.3}, Median: {median}").unwrap();
+    // Anything which still doesn't fit (on a very narrow terminal) is cut off
+    output
+        .lines()
+        .map(|line| line.chars().take(width).collect::<String>() + "\n")
+        .collect()
+}
+
+/// The values worth showing from `dist`: `0`, and then every possible value up
+/// until the tail becomes negligible.
+pub(crate) fn values_to_show(dist: &Distribution) -> Vec<usize> {
+    (0..=dist.max())
+        .filter(|&k| k == 0 || dist.probability(k) > 0.0)
+        .take_while(|&k| k == 0 || dist.at_least(k) >= TAIL_CUTOFF)
+        .collect()
+}
+
+/// A bar `fraction` of `width` long, using the partial block characters for
+/// sub-character precision.
+fn bar(fraction: f64, width: usize) -> String {
+    let eighths = (fraction.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let mut bar = EIGHTHS[7].to_string().repeat(eighths / 8);
+    if !eighths.is_multiple_of(8) {
+        bar.push(EIGHTHS[eighths % 8 - 1]);
+    }
+    bar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::target::Target;
+    use aos_statshammer_core::weapon::WeaponBuilder;
+
+    #[test]
+    fn bar_lengths() {
+        assert_eq!(bar(0.0, 4), "");
+        assert_eq!(bar(1.0, 4), "████");
+        assert_eq!(bar(0.5, 3), "█▌");
+    }
+
+    #[test]
+    fn render_fits_width() {
+        let dist = Distribution::certain(2);
+        let output = render(&dist, 50);
+        assert!(output.lines().all(|l| l.chars().count() <= 50));
+        assert!(output.contains("◀ mean, median"));
+    }
+
+    #[test]
+    fn render_drops_columns_to_fit_narrow_widths() {
+        let weapon = WeaponBuilder::default()
+            .attacks(4)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap();
+        let dist = weapon.damage_distribution(&Target::from(4));
+        let output = render(&dist, 40);
+        assert!(output.lines().all(|l| l.chars().count() <= 40), "{output}");
+        assert!(!output.contains('◀'));
+        assert!(output.contains("P(≥k)"));
+
+        let output = render(&dist, 30);
+        assert!(output.lines().all(|l| l.chars().count() <= 30), "{output}");
+        assert!(!output.contains("P(≥k)"));
+        assert!(output.lines().nth(1).unwrap().contains('█'));
+
+        let output = render(&dist, 10);
+        assert!(output.lines().all(|l| l.chars().count() <= 10), "{output}");
+    }
+}
@@ -0,0 +1,585 @@
+use crate::format::{Formats, Output};
+use crate::serde_utils::*;
+use crate::unit_config::{UnitConfig, UnitWeapon};
+use crate::weapon_config::{RollCharacteristic, ValueCharacteristic, WeaponConfig};
+use anyhow::{Context, Result, bail};
+use aos_statshammer_core::RerollType;
+use clap::{Args, Subcommand};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug)]
+pub(crate) struct ImportArgs {
+    #[command(subcommand)]
+    source: ImportSource,
+}
+
+#[derive(Subcommand, Debug)]
+enum ImportSource {
+    /// Import units exported (as JSON) from the v3 statshammer web app
+    V3(ImportV3Args),
+}
+
+#[derive(Args, Debug)]
+struct ImportV3Args {
+    /// The JSON file exported from the web app
+    file: PathBuf,
+
+    /// The directory to write the unit and weapon files to
+    #[arg(short, long, value_name = "DIR", default_value = ".")]
+    output: PathBuf,
+
+    /// Overwrite any files which already exist
+    #[arg(long)]
+    force: bool,
+}
+
+impl ImportArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        match &self.source {
+            ImportSource::V3(args) => args.execute(formats),
+        }
+    }
+}
+
+impl ImportV3Args {
+    fn execute(&self, formats: &Formats) -> Result<()> {
+        let contents = fs::read_to_string(&self.file)
+            .with_context(|| format!("Unable to read {}", self.file.display()))?;
+        let export: Value = serde_json::from_str(&contents)
+            .with_context(|| format!("Unable to parse {}", self.file.display()))?;
+        let units = convert(&export)?;
+
+        let mut files = vec![];
+        let mut not_imported = vec![];
+        for unit in units {
+            files.push((
+                self.output.join(format!("{}.toml", unit.slug)),
+                toml::to_string(&unit.config)?,
+            ));
+            for (path, weapon) in &unit.weapons {
+                files.push((self.output.join(path), toml::to_string(weapon)?));
+            }
+            not_imported.extend(unit.not_imported);
+        }
+        if !self.force {
+            let existing: Vec<String> = files
+                .iter()
+                .filter(|(path, _)| path.exists())
+                .map(|(path, _)| path.display().to_string())
+                .collect();
+            if !existing.is_empty() {
+                bail!(
+                    "Refusing to overwrite existing files (use --force to overwrite them): {}",
+                    existing.join(", ")
+                );
+            }
+        }
+        for (path, contents) in &files {
+            write(path, contents)?;
+        }
+
+        let output = ImportOutput {
+            files: files.into_iter().map(|(path, _)| path).collect(),
+            not_imported,
+        };
+        print!("{}", formats.render(&output)?);
+        Ok(())
+    }
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Unable to create {}", dir.display()))?;
+    }
+    fs::write(path, contents).with_context(|| format!("Unable to write {}", path.display()))
+}
+
+/// Something from the export which could not be imported exactly.
+#[derive(Serialize, Debug, PartialEq)]
+struct NotImported {
+    unit: String,
+    weapon: String,
+    issue: String,
+}
+
+#[derive(Serialize, Debug)]
+struct ImportOutput {
+    files: Vec<PathBuf>,
+    not_imported: Vec<NotImported>,
+}
+
+impl Output for ImportOutput {
+    fn text(&self) -> String {
+        let mut text = format!("Wrote {} file(s):\n", self.files.len());
+        for path in &self.files {
+            text.push_str(&format!("    {}\n", path.display()));
+        }
+        if !self.not_imported.is_empty() {
+            text.push_str("\nNot imported (or only approximated):\n");
+            for n in &self.not_imported {
+                text.push_str(&format!("    - {} / {}: {}\n", n.unit, n.weapon, n.issue));
+            }
+        }
+        text
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct V3Unit {
+    name: String,
+    #[serde(default)]
+    weapon_profiles: Vec<V3WeaponProfile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct V3WeaponProfile {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default = "default_u32::<1>")]
+    num_models: u32,
+    attacks: Value,
+    to_hit: Value,
+    to_wound: Value,
+    #[serde(default)]
+    rend: Value,
+    damage: Value,
+    #[serde(default)]
+    modifiers: Vec<V3Modifier>,
+}
+
+#[derive(Deserialize, Debug)]
+struct V3Modifier {
+    id: String,
+    #[serde(default)]
+    options: Map<String, Value>,
+}
+
+/// A unit converted from the v3 format, along with its weapons (relative to the output directory).
+#[derive(Debug)]
+struct ImportedUnit {
+    slug: String,
+    config: UnitConfig,
+    weapons: Vec<(PathBuf, WeaponConfig)>,
+    not_imported: Vec<NotImported>,
+}
+
+/// Convert a v3 export, which is either a single unit, a list of units or `{"units": [...]}`.
+fn convert(export: &Value) -> Result<Vec<ImportedUnit>> {
+    let units = match export {
+        Value::Object(object) if object.contains_key("units") => &object["units"],
+        other => other,
+    };
+    let units = match units {
+        Value::Array(units) => units.iter().collect(),
+        unit => vec![unit],
+    };
+
+    let mut slugs = BTreeSet::new();
+    units
+        .into_iter()
+        .enumerate()
+        .map(|(i, unit)| {
+            let unit = V3Unit::deserialize(unit)
+                .with_context(|| format!("Unit {} is not a valid v3 unit", i + 1))?;
+            convert_unit(unit, &mut slugs).with_context(|| format!("Unit {}", i + 1))
+        })
+        .collect()
+}
+
+fn convert_unit(unit: V3Unit, slugs: &mut BTreeSet<String>) -> Result<ImportedUnit> {
+    let slug = unique(slugify(&unit.name, "unit"), slugs);
+    let models = unit
+        .weapon_profiles
+        .iter()
+        .map(|p| p.num_models)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    let mut weapon_slugs = BTreeSet::new();
+    let mut weapons = vec![];
+    let mut unit_weapons = vec![];
+    let mut not_imported = vec![];
+    for (i, profile) in unit.weapon_profiles.iter().enumerate() {
+        let name = profile
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Weapon {}", i + 1));
+        let mut issues = vec![];
+        let config = convert_weapon(profile, &mut issues)
+            .with_context(|| format!("`{}`, weapon `{name}`", unit.name))?;
+        not_imported.extend(issues.into_iter().map(|issue| NotImported {
+            unit: unit.name.clone(),
+            weapon: name.clone(),
+            issue,
+        }));
+
+        let file = Path::new(&slug).join(format!(
+            "{}.toml",
+            unique(slugify(&name, "weapon"), &mut weapon_slugs)
+        ));
+        unit_weapons.push(UnitWeapon {
+            file: file.clone(),
+            models: (profile.num_models != models).then_some(profile.num_models),
+        });
+        weapons.push((file, config));
+    }
+
+    Ok(ImportedUnit {
+        slug,
+        config: UnitConfig {
+            name: unit.name,
+            models,
+            points: None,
+            weapons: unit_weapons,
+        },
+        weapons,
+        not_imported,
+    })
+}
+
+fn convert_weapon(profile: &V3WeaponProfile, issues: &mut Vec<String>) -> Result<WeaponConfig> {
+    let mut value = |value: &Value, name: &str| -> Result<u8> {
+        let mut number = number(value, name, issues)?;
+        // Rend was written as either `-1` or `1`, but nothing else can be negative
+        if name == "rend" {
+            number = number.abs();
+        }
+        if number < 0 {
+            bail!("{name} cannot be negative (got {number})");
+        }
+        u8::try_from(number).with_context(|| format!("{name} is too large (got {number})"))
+    };
+    let mut config = WeaponConfig::new(
+        value(&profile.attacks, "attacks")?,
+        value(&profile.to_hit, "to_hit")?,
+        value(&profile.to_wound, "to_wound")?,
+        value(&profile.rend, "rend")?,
+        value(&profile.damage, "damage")?,
+    );
+    for modifier in &profile.modifiers {
+        if let Err(reason) = convert_modifier(modifier, &mut config, issues) {
+            issues.push(format!("{} modifier {reason}", describe(modifier)));
+        }
+    }
+    Ok(config)
+}
+
+/// Apply a v3 modifier to the config, or return why it cannot be.
+fn convert_modifier(
+    modifier: &V3Modifier,
+    config: &mut WeaponConfig,
+    issues: &mut Vec<String>,
+) -> Result<(), String> {
+    let reroll = |config: &mut WeaponConfig, reroll_type| {
+        config.set_reroll(
+            roll_characteristic(characteristic(modifier)?)?,
+            reroll_type,
+            None,
+        );
+        Ok::<_, String>(())
+    };
+    match modifier.id.as_str() {
+        "REROLL" => reroll(config, RerollType::Any)?,
+        "REROLL_FAILS" => reroll(config, RerollType::Failed)?,
+        "REROLL_ONES" => reroll(config, RerollType::Ones)?,
+        "BONUS" => {
+            let to = characteristic(modifier)?;
+            let amount = match option(modifier, "bonus") {
+                Some(bonus) => number(bonus, "bonus", issues).map_err(|err| format!("{err}"))?,
+                None => 1,
+            };
+            let amount = i16::try_from(amount).map_err(|_| "has a bonus which is too large")?;
+            config.add_bonus(to, amount, None);
+        }
+        "EXPLODING" => {
+            let to = roll_characteristic(characteristic(modifier)?)?;
+            let on = option(modifier, "on").and_then(Value::as_u64).unwrap_or(6);
+            let extra = option(modifier, "extra_hits")
+                .or_else(|| option(modifier, "extra"))
+                .and_then(Value::as_u64)
+                .unwrap_or(1);
+            let unmodified = option(modifier, "unmodified")
+                .and_then(Value::as_bool)
+                .unwrap_or(true);
+            let (Ok(on), Ok(extra)) = (u8::try_from(on), u8::try_from(extra)) else {
+                return Err("has values which are too large".into());
+            };
+            config.set_exploding(to, on, unmodified, extra, None);
+        }
+        "MORTAL_WOUNDS" => {
+            return Err("was not imported (mortal wounds are not supported yet)".into());
+        }
+        "CONDITIONAL_BONUS" => {
+            return Err("was not imported (conditional bonuses are not supported yet)".into());
+        }
+        "LEADER_BONUS" | "LEADER_EXTRA_ATTACKS" => {
+            return Err("was not imported (leader abilities are not supported yet)".into());
+        }
+        _ => return Err("was not imported (it has no v4 equivalent)".into()),
+    }
+    Ok(())
+}
+
+fn characteristic(modifier: &V3Modifier) -> Result<ValueCharacteristic, String> {
+    option(modifier, "characteristic")
+        .and_then(Value::as_str)
+        .and_then(value_characteristic)
+        .ok_or_else(|| "was not imported (its characteristic does not exist in v4)".into())
+}
+
+/// The modifier id, along with the characteristic it applies to (if any).
+fn describe(modifier: &V3Modifier) -> String {
+    match option(modifier, "characteristic").and_then(Value::as_str) {
+        Some(characteristic) => format!("{} ({characteristic})", modifier.id),
+        None => modifier.id.clone(),
+    }
+}
+
+/// A modifier option, which may be wrapped in `{"value": ...}`.
+fn option<'a>(modifier: &'a V3Modifier, key: &str) -> Option<&'a Value> {
+    let value = modifier.options.get(key)?;
+    Some(value.get("value").unwrap_or(value))
+}
+
+fn value_characteristic(name: &str) -> Option<ValueCharacteristic> {
+    match name {
+        "attacks" => Some(ValueCharacteristic::Attacks),
+        "to_hit" | "hit" => Some(ValueCharacteristic::Hit),
+        "to_wound" | "wound" => Some(ValueCharacteristic::Wound),
+        "rend" => Some(ValueCharacteristic::Rend),
+        "damage" => Some(ValueCharacteristic::Damage),
+        _ => None,
+    }
+}
+
+fn roll_characteristic(to: ValueCharacteristic) -> Result<RollCharacteristic, String> {
+    match to {
+        ValueCharacteristic::Hit => Ok(RollCharacteristic::Hit),
+        ValueCharacteristic::Wound => Ok(RollCharacteristic::Wound),
+        _ => Err("was not imported (only hit and wound rolls can be rerolled or explode)".into()),
+    }
+}
+
+/// A v3 value, which is either a number, a string such as `"3+"` or `"-1"`, or a dice
+/// expression such as `"D6"` or `"2D3+1"`. Dice are replaced by their rounded average,
+/// since v4 profiles only have fixed values (this is noted as an issue).
+fn number(value: &Value, name: &str, issues: &mut Vec<String>) -> Result<i64> {
+    match value {
+        Value::Null => Ok(0),
+        Value::Number(n) => n
+            .as_i64()
+            .or_else(|| n.as_f64().map(|f| f.round() as i64))
+            .with_context(|| format!("invalid {name} `{n}`")),
+        Value::String(s) => {
+            let s = s.trim();
+            if let Ok(n) = s.strip_suffix('+').unwrap_or(s).parse() {
+                return Ok(n);
+            }
+            match dice_average(s) {
+                Some(average) => {
+                    let rounded = average.round() as i64;
+                    issues.push(format!(
+                        "{name} `{s}` is random, so its rounded average ({rounded}) was used instead"
+                    ));
+                    Ok(rounded)
+                }
+                None => bail!("invalid {name} `{s}`"),
+            }
+        }
+        other => bail!("invalid {name} `{other}`"),
+    }
+}
+
+/// The average of a dice expression such as `D6`, `2D3` or `D3+1`.
+fn dice_average(expression: &str) -> Option<f64> {
+    expression
+        .to_ascii_uppercase()
+        .split('+')
+        .map(|term| match term.trim().split_once('D') {
+            Some((count, sides)) => {
+                let count: u32 = if count.is_empty() {
+                    1
+                } else {
+                    count.parse().ok()?
+                };
+                let sides: u32 = sides.parse().ok()?;
+                Some(count as f64 * (sides as f64 + 1.0) / 2.0)
+            }
+            None => term.trim().parse::<u32>().ok().map(f64::from),
+        })
+        .sum()
+}
+
+/// A file name for a unit or weapon, e.g. `Celestar Ballista` becomes `celestar-ballista`.
+fn slugify(name: &str, fallback: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        fallback.to_string()
+    } else {
+        slug
+    }
+}
+
+/// Add a numbered suffix to the slug if it has already been used.
+fn unique(slug: String, used: &mut BTreeSet<String>) -> String {
+    let mut candidate = slug.clone();
+    let mut n = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{slug}-{n}");
+        n += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::weapon::Weapon;
+    use serde_json::json;
+
+    fn liberators() -> Value {
+        json!({
+            "name": "Liberators",
+            "weapon_profiles": [
+                {
+                    "name": "Warhammer",
+                    "num_models": 5,
+                    "attacks": 2,
+                    "to_hit": "3+",
+                    "to_wound": 3,
+                    "rend": "-1",
+                    "damage": 1,
+                    "modifiers": [
+                        { "id": "REROLL_ONES", "options": { "characteristic": "to_hit" } },
+                        { "id": "BONUS", "options": { "characteristic": "to_wound", "bonus": 1 } },
+                        {
+                            "id": "EXPLODING",
+                            "options": { "characteristic": "to_hit", "on": 6, "unmodified": true, "extra_hits": 1 }
+                        },
+                        { "id": "MORTAL_WOUNDS", "options": { "characteristic": "to_hit", "on": 6 } }
+                    ]
+                },
+                {
+                    "name": "Grandhammer",
+                    "num_models": 1,
+                    "attacks": 2,
+                    "to_hit": 4,
+                    "to_wound": 3,
+                    "rend": 1,
+                    "damage": "D3"
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn converts_characteristics_and_modifiers() {
+        let units = convert(&liberators()).unwrap();
+        assert_eq!(units.len(), 1);
+        let unit = &units[0];
+        assert_eq!(unit.slug, "liberators");
+        assert_eq!(unit.config.models, 5);
+        assert_eq!(unit.config.weapons[0].models, None);
+        assert_eq!(unit.config.weapons[1].models, Some(1));
+        assert_eq!(
+            unit.weapons[0].0,
+            Path::new("liberators").join("warhammer.toml")
+        );
+
+        let warhammer = toml::to_string(&unit.weapons[0].1).unwrap();
+        let warhammer: Weapon = toml::from_str::<WeaponConfig>(&warhammer)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(warhammer.hit.value, 3);
+        assert_eq!(warhammer.rend.value, 1);
+        assert_eq!(warhammer.hit.reroll, Some(RerollType::Ones));
+        assert_eq!(warhammer.wound.bonus, 1);
+        assert_eq!(
+            warhammer
+                .hit
+                .exploding
+                .map(|e| (e.on, e.unmodified, e.extra)),
+            Some((6, true, 1))
+        );
+    }
+
+    #[test]
+    fn lists_what_was_not_imported() {
+        let units = convert(&liberators()).unwrap();
+        let issues: Vec<_> = units[0]
+            .not_imported
+            .iter()
+            .map(|n| (n.weapon.as_str(), n.issue.as_str()))
+            .collect();
+        assert_eq!(
+            issues,
+            [
+                (
+                    "Warhammer",
+                    "MORTAL_WOUNDS (to_hit) modifier was not imported (mortal wounds are not supported yet)"
+                ),
+                (
+                    "Grandhammer",
+                    "damage `D3` is random, so its rounded average (2) was used instead"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_lists_of_units() {
+        let list = json!([liberators(), liberators()]);
+        let slugs: Vec<_> = convert(&list)
+            .unwrap()
+            .into_iter()
+            .map(|u| u.slug)
+            .collect();
+        assert_eq!(slugs, ["liberators", "liberators-2"]);
+        let wrapped = json!({ "units": [liberators()] });
+        assert_eq!(convert(&wrapped).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn invalid_values_are_errors() {
+        let mut unit = liberators();
+        unit["weapon_profiles"][0]["attacks"] = json!("lots");
+        let err = convert(&unit).unwrap_err();
+        assert!(format!("{err:#}").contains("invalid attacks `lots`"));
+
+        let mut unit = liberators();
+        unit["weapon_profiles"][0]["damage"] = json!(-2);
+        let err = convert(&unit).unwrap_err();
+        assert!(format!("{err:#}").contains("damage cannot be negative (got -2)"));
+        let mut unit = liberators();
+        unit["weapon_profiles"][0]["rend"] = json!(-2);
+        assert!(convert(&unit).is_ok());
+    }
+
+    #[test]
+    fn dice_averages() {
+        assert_eq!(dice_average("D6"), Some(3.5));
+        assert_eq!(dice_average("2d3+1"), Some(5.0));
+        assert_eq!(dice_average("lots"), None);
+    }
+
+    #[test]
+    fn slugs() {
+        assert_eq!(slugify("Celestar Ballista", "unit"), "celestar-ballista");
+        assert_eq!(
+            slugify("Lord-Celestant (on Dracoth)", "unit"),
+            "lord-celestant-on-dracoth"
+        );
+        assert_eq!(slugify("!!", "weapon"), "weapon");
+    }
+}
@@ -0,0 +1,187 @@
+use crate::format::Formats;
+use crate::overrides::Overrides;
+use crate::target_config::TargetConfig;
+use crate::unit_config::UnitConfig;
+use crate::weapon_config::WeaponConfig;
+use anyhow::{Result, bail};
+use aos_statshammer_core::target::Target;
+use aos_statshammer_core::unit::{Unit, UnitWeapon};
+use aos_statshammer_core::weapon::Weapon;
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+/// Load a weapon from a config file, applying any command line overrides.
+pub(crate) fn load_weapon(path: &Path, overrides: &Overrides, formats: &Formats) -> Result<Weapon> {
+    load_weapon_config(path, overrides, formats)?.try_into()
+}
+
+/// Load a weapon config, applying any command line overrides (which are kept as
+/// named abilities, unlike in the [`Weapon`]).
+pub(crate) fn load_weapon_config(
+    path: &Path,
+    overrides: &Overrides,
+    formats: &Formats,
+) -> Result<WeaponConfig> {
+    let mut weapon_config: WeaponConfig = formats.read(path)?;
+    overrides.apply_to_weapon(&mut weapon_config)?;
+    Ok(weapon_config)
+}
+
+/// Load several weapons, labelling each one by its file name.
+pub(crate) fn load_weapons(
+    paths: &[PathBuf],
+    overrides: &Overrides,
+    formats: &Formats,
+) -> Result<Vec<(String, Weapon)>> {
+    paths
+        .iter()
+        .map(|path| Ok((label(path), load_weapon(path, overrides, formats)?)))
+        .collect()
+}
+
+/// Load a unit and its weapons (which are relative to the unit file), applying
+/// any command line overrides to every weapon.
+pub(crate) fn load_unit(
+    path: &Path,
+    overrides: &Overrides,
+    formats: &Formats,
+) -> Result<(UnitConfig, Unit)> {
+    let path = formats.resolve(path)?;
+    let config: UnitConfig = formats.read(&path)?;
+    let problems = config.problems();
+    if !problems.is_empty() {
+        bail!(
+            "{} is not a valid unit: {}",
+            path.display(),
+            problems.join("; ")
+        );
+    }
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let weapons = config
+        .weapons
+        .iter()
+        .map(|w| {
+            Ok(UnitWeapon {
+                weapon: load_weapon(&dir.join(&w.file), overrides, formats)?,
+                models: w.models.unwrap_or(config.models),
+            })
+        })
+        .collect::<Result<_>>()?;
+    let unit = Unit {
+        models: config.models,
+        weapons,
+    };
+    Ok((config, unit))
+}
+
+/// The label for a profile loaded from `path` (its file name).
+pub(crate) fn label(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+// The options used to describe the target of an attack.
+#[derive(Args, Debug, Clone)]
+pub(crate) struct TargetArgs {
+    #[arg(short, long)]
+    save: Option<u8>,
+
+    #[arg(short, long, value_name = "FILE")]
+    target: Option<PathBuf>,
+}
+
+impl TargetArgs {
+    /// Load the target, or use `default` if no target was given.
+    pub fn load_or(
+        &self,
+        default: Target,
+        overrides: &Overrides,
+        formats: &Formats,
+    ) -> Result<Target> {
+        match self.load_config(overrides, formats)? {
+            Some(target_config) => target_config.try_into(),
+            None => Ok(default),
+        }
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.target.as_deref()
+    }
+
+    /// Load the target config (if any), applying `--save` and any command line overrides.
+    pub fn load_config(
+        &self,
+        overrides: &Overrides,
+        formats: &Formats,
+    ) -> Result<Option<TargetConfig>> {
+        let mut target_config: Option<TargetConfig> = None;
+        if let Some(target_file) = &self.target {
+            target_config = Some(formats.read(target_file)?);
+        }
+        if let Some(save) = self.save {
+            match target_config {
+                Some(ref mut target_config) => target_config.set_save(save),
+                None => target_config = Some(TargetConfig::from_save(save)),
+            }
+        }
+        overrides.apply_to_target(&mut target_config)?;
+        if let Some(problems) = target_config.as_ref().map(TargetConfig::problems)
+            && !problems.is_empty()
+        {
+            bail!("The target is not valid: {}", problems.join("; "));
+        }
+        Ok(target_config)
+    }
+
+    pub fn load(&self, overrides: &Overrides, formats: &Formats) -> Result<Target> {
+        match self.load_config(overrides, formats)? {
+            Some(target_config) => target_config.try_into(),
+            None => bail!("No target specified. Please use either --save or --target."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn invalid_units_are_errors() {
+        let path = std::env::temp_dir().join("aos-statshammer-too-many-models.toml");
+        fs::write(
+            &path,
+            "name = \"Stabbas\"\nmodels = 10\n\n[[weapons]]\nfile = \"stabba.toml\"\nmodels = 20\n",
+        )
+        .unwrap();
+        let err = load_unit(&path, &Overrides::default(), &Formats::default()).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(
+            format!("{err:#}").contains("is not a valid unit"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn invalid_targets_are_errors() {
+        let path = std::env::temp_dir().join("aos-statshammer-impossible-ward.toml");
+        fs::write(
+            &path,
+            "[characteristics]\nsave = 4\n\n[abilities]\nward = 1\n",
+        )
+        .unwrap();
+        let args = TargetArgs {
+            save: None,
+            target: Some(path.clone()),
+        };
+        let err = args
+            .load(&Overrides::default(), &Formats::default())
+            .unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            err.to_string(),
+            "The target is not valid: ward must be between 2 and 6 (got 1)"
+        );
+    }
+}
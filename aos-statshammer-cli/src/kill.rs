@@ -0,0 +1,144 @@
+use crate::format::{Formats, Output, text_table};
+use crate::input::{TargetArgs, load_unit, load_weapon};
+use crate::overrides::Overrides;
+use anyhow::Result;
+use aos_statshammer_core::distribution::Distribution;
+use aos_statshammer_core::target::Target;
+use clap::{ArgGroup, Args};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("attacker").required(true).args(["weapon", "unit"])))]
+pub(crate) struct KillArgs {
+    /// The weapon to attack with (a single model)
+    #[arg(short, long, value_name = "FILE")]
+    weapon: Option<PathBuf>,
+
+    /// The unit to attack with (every model, with every weapon)
+    #[arg(short, long, value_name = "FILE")]
+    unit: Option<PathBuf>,
+
+    // The target's health and models come from the target file, or `--set target.health=N`
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// Only show the probability of slaying at least this many models [default: every number of models]
+    #[arg(long, value_name = "MODELS")]
+    at_least: Vec<u32>,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl KillArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let target = self.target.load(&self.overrides, formats)?;
+        let damage = match (&self.weapon, &self.unit) {
+            (Some(path), _) => {
+                load_weapon(path, &self.overrides, formats)?.damage_distribution(&target)
+            }
+            (None, Some(path)) => load_unit(path, &self.overrides, formats)?
+                .1
+                .damage_distribution(&target),
+            (None, None) => unreachable!("clap requires an attacker"),
+        };
+        let output = KillOutput::new(&target, &damage, &self.at_least);
+        print!("{}", formats.render(&output)?);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct KillOutput {
+    health: u32,
+    models: u32,
+    average_models_slain: f64,
+    /// `P(slain >= models)` for each number of models asked about.
+    at_least: Vec<KillProbability>,
+    destroy_probability: f64,
+}
+
+#[derive(Serialize, Debug)]
+struct KillProbability {
+    models: u32,
+    probability: f64,
+}
+
+impl KillOutput {
+    fn new(target: &Target, damage: &Distribution, at_least: &[u32]) -> Self {
+        let models: Vec<u32> = if at_least.is_empty() {
+            (1..=target.models).collect()
+        } else {
+            at_least.to_vec()
+        };
+        Self {
+            health: target.health,
+            models: target.models,
+            average_models_slain: target.models_slain(damage).mean(),
+            at_least: models
+                .into_iter()
+                .map(|models| KillProbability {
+                    models,
+                    probability: target.kill_probability(damage, models),
+                })
+                .collect(),
+            destroy_probability: target.destroy_probability(damage),
+        }
+    }
+}
+
+impl Output for KillOutput {
+    fn text(&self) -> String {
+        let mut text = format!(
+            "Target: {} model(s) with {} health\nAverage Models Slain: {:.3}\n\n",
+            self.models, self.health, self.average_models_slain
+        );
+        let headers = ["Slain", "Probability"].map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .at_least
+            .iter()
+            .map(|k| {
+                vec![
+                    format!("at least {}", k.models),
+                    format!("{:.1}%", k.probability * 100.0),
+                ]
+            })
+            .collect();
+        text.push_str(&text_table(&headers, &rows));
+        text.push_str(&format!(
+            "\nDestroyed: {:.1}%\n",
+            self.destroy_probability * 100.0
+        ));
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::target::TargetBuilder;
+
+    #[test]
+    fn every_model_by_default() {
+        let target = TargetBuilder::default()
+            .save(4)
+            .health(2)
+            .models(3)
+            .build()
+            .unwrap();
+        let damage = Distribution::certain(4);
+        let output = KillOutput::new(&target, &damage, &[]);
+        let at_least: Vec<_> = output
+            .at_least
+            .iter()
+            .map(|k| (k.models, k.probability))
+            .collect();
+        assert_eq!(at_least, [(1, 1.0), (2, 1.0), (3, 0.0)]);
+        assert_eq!(output.destroy_probability, 0.0);
+        assert_eq!(output.average_models_slain, 2.0);
+
+        let output = KillOutput::new(&target, &damage, &[3]);
+        assert_eq!(output.at_least.len(), 1);
+    }
+}
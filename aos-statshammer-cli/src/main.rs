@@ -1,35 +1,160 @@
-mod rerolls;
+mod analyze;
+mod attrition;
+mod avg;
+mod catalog;
+mod chart;
+mod combat;
+mod compare;
+mod dist;
+mod efficiency;
+mod explain;
+mod export_chart;
+mod format;
+mod histogram;
+mod import;
+mod input;
+mod kill;
+mod optimize;
+mod overrides;
+mod report;
 mod serde_utils;
+mod serve;
+mod simulate;
+mod summary;
+mod table;
 mod target_config;
+mod unit_config;
+mod validate;
+mod watch;
 mod weapon_config;
 
-use crate::target_config::TargetConfig;
-use crate::weapon_config::WeaponConfig;
-use anyhow::{Result, bail};
-use aos_statshammer_core::target::Target;
-use aos_statshammer_core::weapon::Weapon;
-use clap::Parser;
-use log::debug;
-use log::{LevelFilter, info};
+use crate::analyze::AnalyzeArgs;
+use crate::attrition::AttritionArgs;
+use crate::avg::AvgArgs;
+use crate::catalog::CatalogArgs;
+use crate::combat::CombatArgs;
+use crate::compare::CompareArgs;
+use crate::dist::DistArgs;
+use crate::efficiency::EfficiencyArgs;
+use crate::explain::ExplainArgs;
+use crate::export_chart::ExportChartArgs;
+use crate::format::Formats;
+use crate::import::ImportArgs;
+use crate::kill::KillArgs;
+use crate::optimize::OptimizeArgs;
+use crate::report::ReportArgs;
+use crate::serve::ServeArgs;
+use crate::simulate::SimulateArgs;
+use crate::table::TableArgs;
+use crate::validate::ValidateArgs;
+use anyhow::Result;
+use clap::{CommandFactory, Parser, Subcommand};
+use log::LevelFilter;
 use simple_logger::SimpleLogger;
-use std::fs;
-use std::path::PathBuf;
+use std::ffi::OsString;
 
 #[derive(Parser, Debug)]
 struct Cli {
-    #[arg(short, long, value_name = "FILE")]
-    weapon: PathBuf,
+    #[command(subcommand)]
+    command: Command,
 
-    #[arg(short, long)]
-    save: Option<u8>,
+    #[command(flatten)]
+    formats: Formats,
 
-    #[arg(short, long, value_name = "FILE")]
-    target: Option<PathBuf>,
-
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     verbose: bool,
 }
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Calculate the average damage of a weapon (the default)
+    Avg(AvgArgs),
+    /// Show the exact damage distribution of a weapon
+    Dist(DistArgs),
+    /// Show the average damage of one or more weapons against every save
+    Table(TableArgs),
+    /// Estimate the damage distribution by rolling dice
+    Simulate(SimulateArgs),
+    /// Compare the average damage of two or more weapons
+    Compare(CompareArgs),
+    /// The probability of slaying a number of models, or destroying the target
+    Kill(KillArgs),
+    /// Fight over several battle rounds, with losses reducing each unit's attacks
+    Attrition(AttritionArgs),
+    /// Two units fight each other once, in strike order, with losses reducing the retaliation
+    Combat(CombatArgs),
+    /// Compare the damage per point of one or more units
+    Efficiency(EfficiencyArgs),
+    /// Rank the damage gained from each single improvement to a weapon
+    Analyze(AnalyzeArgs),
+    /// Find the combination of buffs which does the most damage within a budget
+    Optimize(OptimizeArgs),
+    /// Check weapon and target files for mistakes
+    Validate(ValidateArgs),
+    /// Walk through the average damage calculation step by step
+    Explain(ExplainArgs),
+    /// Export an SVG chart comparing one or more weapons
+    ExportChart(ExportChartArgs),
+    /// Write a self-contained HTML report comparing one or more weapons
+    Report(ReportArgs),
+    /// Run as a long-lived JSON-RPC server
+    Serve(ServeArgs),
+    /// Import profiles from other tools
+    Import(ImportArgs),
+    /// Search the catalog of unit and weapon profiles
+    Catalog(CatalogArgs),
+}
+
 impl Cli {
+    /// Parse the command line, running `avg` when no subcommand is given (so that
+    /// e.g. `-w weapon.toml -s 4` works on its own). The global options can come
+    /// before or after the subcommand either way.
+    fn try_parse_with_default<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        if let Some(index) = Self::default_subcommand_index(&args) {
+            args.insert(index, "avg".into());
+        }
+        Self::try_parse_from(args)
+    }
+
+    /// Where `avg` should be inserted, if the first argument which isn't a global
+    /// option (or the value of one) isn't a subcommand.
+    fn default_subcommand_index(args: &[OsString]) -> Option<usize> {
+        let command = Self::command();
+        let global = |arg: &str| {
+            command.get_arguments().find(|a| {
+                arg.strip_prefix("--")
+                    .is_some_and(|long| a.get_long() == Some(long.split('=').next().unwrap()))
+                    || arg
+                        .strip_prefix('-')
+                        .and_then(|short| short.chars().next())
+                        .is_some_and(|short| a.get_short() == Some(short))
+            })
+        };
+        let mut index = 1;
+        while let Some(arg) = args.get(index) {
+            let arg = arg.to_string_lossy();
+            if matches!(&*arg, "-h" | "--help" | "-V" | "--version") {
+                return None;
+            }
+            match global(&arg) {
+                Some(option) if option.get_action().takes_values() && !arg.contains('=') => {
+                    index += 2
+                }
+                Some(_) => index += 1,
+                None if arg == "help" || command.find_subcommand(&*arg).is_some() => {
+                    return None;
+                }
+                None => return Some(index),
+            }
+        }
+        None
+    }
+
     pub fn execute(&self) -> Result<()> {
         SimpleLogger::new().init()?;
         if self.verbose {
@@ -38,37 +163,72 @@ impl Cli {
             log::set_max_level(LevelFilter::Info);
         }
 
-        let contents = fs::read_to_string(&self.weapon)?;
-        let weapon_config: WeaponConfig = toml::from_str(&contents)?;
-        let weapon: Weapon = weapon_config.try_into()?;
-
-        let mut target: Option<Target> = None;
-        if let Some(target_file) = &self.target {
-            let target_contents = fs::read_to_string(target_file)?;
-            let target_config: TargetConfig = toml::from_str(&target_contents)?;
-            target = Some(target_config.try_into()?);
-        }
-        if let Some(save) = self.save {
-            match target {
-                Some(ref mut target) => target.save = save.into(),
-                None => target = Some(save.into()),
-            }
+        let formats = &self.formats;
+        match &self.command {
+            Command::Avg(args) => args.execute(formats),
+            Command::Dist(args) => args.execute(formats),
+            Command::Table(args) => args.execute(formats),
+            Command::Simulate(args) => args.execute(formats),
+            Command::Compare(args) => args.execute(formats),
+            Command::Kill(args) => args.execute(formats),
+            Command::Attrition(args) => args.execute(formats),
+            Command::Combat(args) => args.execute(formats),
+            Command::Efficiency(args) => args.execute(formats),
+            Command::Analyze(args) => args.execute(formats),
+            Command::Optimize(args) => args.execute(formats),
+            Command::Validate(args) => args.execute(formats),
+            Command::Explain(args) => args.execute(formats),
+            Command::ExportChart(args) => args.execute(formats),
+            Command::Report(args) => args.execute(formats),
+            Command::Serve(args) => args.execute(formats),
+            Command::Import(args) => args.execute(formats),
+            Command::Catalog(args) => args.execute(formats),
         }
-
-        match target {
-            Some(target) => {
-                debug!("{:?}", &weapon);
-                debug!("{:?}", &target);
-                info!("Average Damage: {:.3}", weapon.average_damage(&target))
-            }
-            None => bail!("No target specified. Please use either --save or --target."),
-        }
-
-        Ok(())
     }
 }
 
 fn main() {
-    let args = Cli::parse();
+    let args = Cli::try_parse_with_default(std::env::args_os()).unwrap_or_else(|err| err.exit());
     args.execute().unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::OutputFormat;
+    use std::path::PathBuf;
+
+    fn parse(args: &str) -> Cli {
+        Cli::try_parse_with_default(args.split_whitespace()).unwrap()
+    }
+
+    #[test]
+    fn global_options_go_either_side_of_the_subcommand() {
+        for args in [
+            "aos --output-format json -v --catalog cat table -w a.toml",
+            "aos table -w a.toml --output-format json -v --catalog cat",
+            "aos --output-format=json table -v -w a.toml --catalog=cat",
+        ] {
+            let cli = parse(args);
+            assert!(matches!(cli.command, Command::Table(_)), "{args}");
+            assert_eq!(cli.formats.output_format, OutputFormat::Json, "{args}");
+            assert_eq!(cli.formats.catalog, PathBuf::from("cat"), "{args}");
+            assert!(cli.verbose, "{args}");
+        }
+    }
+
+    #[test]
+    fn avg_is_the_default_subcommand() {
+        for args in [
+            "aos -w a.toml -s 4",
+            "aos --output-format json -w a.toml -s 4",
+            "aos -v --catalog table -w a.toml -s 4",
+            "aos avg -w a.toml -s 4 -v",
+        ] {
+            let cli = parse(args);
+            assert!(matches!(cli.command, Command::Avg(_)), "{args}");
+        }
+        assert!(Cli::try_parse_with_default(["aos", "--help"]).is_err());
+        assert!(Cli::try_parse_with_default(["aos", "table", "--bogus"]).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use crate::format::{Formats, Output, text_table};
+use crate::input::TargetArgs;
+use crate::overrides::Overrides;
+use crate::target_config::TargetConfig;
+use crate::weapon_config::WeaponConfig;
+use anyhow::{Context, Result, bail};
+use aos_statshammer_core::target::Target;
+use aos_statshammer_core::weapon::Weapon;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The most combinations of buffs to try, as every one is calculated.
+const MAX_COMBINATIONS: usize = 100_000;
+
+#[derive(Args, Debug)]
+pub(crate) struct OptimizeArgs {
+    #[arg(short, long, value_name = "FILE")]
+    weapon: PathBuf,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// The buffs which may be used, with their costs and groups
+    #[arg(short, long, value_name = "FILE")]
+    buffs: PathBuf,
+
+    /// The most that may be spent on buffs [default: no limit]
+    #[arg(long)]
+    budget: Option<u32>,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+/// The buffs file, e.g.
+///
+/// ```toml
+/// [[buffs]]
+/// name = "All-out Attack"
+/// cost = 1
+/// group = "command"
+/// effects = { bonus = ["hit=+1"] }
+/// ```
+#[derive(Deserialize, Debug)]
+struct BuffMenu {
+    #[serde(default)]
+    buffs: Vec<Buff>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct Buff {
+    name: String,
+    /// What the buff costs, in whatever the budget is in (command points, casting
+    /// value, points...).
+    cost: u32,
+    /// At most one buff from each group can be used together.
+    #[serde(default)]
+    group: Option<String>,
+    /// What the buff does, with the same syntax as the command line overrides.
+    effects: Overrides,
+}
+
+impl OptimizeArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let menu: BuffMenu = formats.read(&self.buffs)?;
+        if menu.buffs.is_empty() {
+            bail!("There are no buffs in {}", self.buffs.display());
+        }
+        let weapon: WeaponConfig = formats.read(&self.weapon)?;
+        let target = self.target.load_config(&Overrides::default(), formats)?;
+
+        let combinations = combinations(&menu.buffs)?
+            .into_iter()
+            .map(|buffs| {
+                let overrides = buffs.iter().fold(self.overrides.clone(), |o, &i| {
+                    o.and(&menu.buffs[i].effects)
+                });
+                let (weapon, target) = apply(&weapon, &target, &overrides)?;
+                Ok(Combination {
+                    buffs: buffs.iter().map(|&i| menu.buffs[i].name.clone()).collect(),
+                    cost: buffs.iter().map(|&i| menu.buffs[i].cost).sum(),
+                    average_damage: weapon.average_damage(&target),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let output = OptimizeOutput::new(self.budget, combinations);
+        print!("{}", formats.render(&output)?);
+        Ok(())
+    }
+}
+
+fn apply(
+    weapon: &WeaponConfig,
+    target: &Option<TargetConfig>,
+    overrides: &Overrides,
+) -> Result<(Weapon, Target)> {
+    let mut weapon = weapon.clone();
+    overrides.apply_to_weapon(&mut weapon)?;
+    let mut target = target.clone();
+    overrides.apply_to_target(&mut target)?;
+    let target = target.context("No target specified. Please use either --save or --target.")?;
+    Ok((weapon.try_into()?, target.try_into()?))
+}
+
+/// Every combination of buffs (as indices into `buffs`), with at most one from each group.
+fn combinations(buffs: &[Buff]) -> Result<Vec<Vec<usize>>> {
+    // Each buff without a group can be chosen on its own, otherwise one from each group
+    let mut choices: Vec<(Option<&str>, Vec<usize>)> = vec![];
+    for (i, buff) in buffs.iter().enumerate() {
+        let group = buff.group.as_deref();
+        match choices
+            .iter_mut()
+            .find(|(g, _)| group.is_some() && *g == group)
+        {
+            Some((_, indices)) => indices.push(i),
+            None => choices.push((group, vec![i])),
+        }
+    }
+    let count = choices
+        .iter()
+        .try_fold(1usize, |n, (_, indices)| n.checked_mul(indices.len() + 1));
+    if count.is_none_or(|n| n > MAX_COMBINATIONS) {
+        bail!("There are too many combinations of buffs to try (the most is {MAX_COMBINATIONS})");
+    }
+
+    let mut combinations = vec![vec![]];
+    for (_, indices) in &choices {
+        let mut next = vec![];
+        for combination in &combinations {
+            next.push(combination.clone());
+            for &i in indices {
+                let mut with = combination.clone();
+                with.push(i);
+                next.push(with);
+            }
+        }
+        combinations = next;
+    }
+    Ok(combinations)
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct Combination {
+    buffs: Vec<String>,
+    cost: u32,
+    average_damage: f64,
+}
+
+#[derive(Serialize, Debug)]
+struct OptimizeOutput {
+    budget: Option<u32>,
+    /// The damage without any buffs.
+    average_damage: f64,
+    /// The most damage within the budget (for the least cost).
+    best: Combination,
+    /// The combinations which do more damage than anything cheaper, by cost.
+    frontier: Vec<Combination>,
+}
+
+impl OptimizeOutput {
+    fn new(budget: Option<u32>, mut combinations: Vec<Combination>) -> Self {
+        let average_damage = combinations
+            .iter()
+            .find(|c| c.buffs.is_empty())
+            .map_or(0.0, |c| c.average_damage);
+        combinations.sort_by(|a, b| {
+            a.cost
+                .cmp(&b.cost)
+                .then(b.average_damage.total_cmp(&a.average_damage))
+        });
+        let mut frontier: Vec<Combination> = vec![];
+        for combination in combinations {
+            // Ignore differences in the last few bits, so equal damage isn't on the frontier twice
+            let better = frontier
+                .last()
+                .is_none_or(|last| combination.average_damage > last.average_damage + 1e-9);
+            if better {
+                frontier.push(combination);
+            }
+        }
+        let best = frontier
+            .iter()
+            .rev()
+            .find(|c| budget.is_none_or(|budget| c.cost <= budget))
+            .expect("using no buffs costs nothing")
+            .clone();
+        Self {
+            budget,
+            average_damage,
+            best,
+            frontier,
+        }
+    }
+}
+
+fn names(combination: &Combination) -> String {
+    if combination.buffs.is_empty() {
+        "-".to_string()
+    } else {
+        combination.buffs.join(" + ")
+    }
+}
+
+impl Output for OptimizeOutput {
+    fn text(&self) -> String {
+        let within = self
+            .budget
+            .map_or(String::new(), |budget| format!(" (budget {budget})"));
+        let mut text = format!(
+            "Without Buffs: {:.3}\nBest{within}: {} for {}, {:.3} damage\n\nFrontier:\n",
+            self.average_damage,
+            names(&self.best),
+            self.best.cost,
+            self.best.average_damage
+        );
+        let headers = ["Cost", "Damage", "Buffs"].map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .frontier
+            .iter()
+            .map(|c| {
+                vec![
+                    c.cost.to_string(),
+                    format!("{:.3}", c.average_damage),
+                    names(c),
+                ]
+            })
+            .collect();
+        text.push_str(&text_table(&headers, &rows));
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buff(name: &str, group: Option<&str>) -> Buff {
+        Buff {
+            name: name.to_string(),
+            cost: 1,
+            group: group.map(String::from),
+            effects: Overrides::default(),
+        }
+    }
+
+    fn combination(buffs: &[&str], cost: u32, average_damage: f64) -> Combination {
+        Combination {
+            buffs: buffs.iter().map(|b| b.to_string()).collect(),
+            cost,
+            average_damage,
+        }
+    }
+
+    #[test]
+    fn at_most_one_from_each_group() {
+        let buffs = [
+            buff("a", Some("spell")),
+            buff("b", Some("spell")),
+            buff("c", None),
+            buff("d", None),
+        ];
+        let combinations = combinations(&buffs).unwrap();
+        assert_eq!(combinations.len(), 3 * 2 * 2);
+        assert!(
+            !combinations
+                .iter()
+                .any(|c| c.contains(&0) && c.contains(&1))
+        );
+        assert!(combinations.contains(&vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn frontier_and_best_within_budget() {
+        let output = OptimizeOutput::new(
+            Some(2),
+            vec![
+                combination(&[], 0, 1.0),
+                combination(&["a"], 1, 1.5),
+                combination(&["b"], 2, 1.2),
+                combination(&["a", "b"], 3, 2.0),
+            ],
+        );
+        let frontier: Vec<_> = output.frontier.iter().map(|c| c.cost).collect();
+        assert_eq!(frontier, [0, 1, 3]);
+        assert_eq!(output.best.buffs, ["a"]);
+        assert_eq!(output.average_damage, 1.0);
+    }
+
+    #[test]
+    fn read_buffs() {
+        let menu: BuffMenu = toml::from_str(
+            r#"
+            [[buffs]]
+            name = "All-out Attack"
+            cost = 1
+            group = "command"
+            effects = { bonus = ["hit=+1"] }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(menu.buffs[0].group.as_deref(), Some("command"));
+    }
+
+    #[test]
+    fn misspelled_or_missing_keys_are_errors() {
+        let read = |toml: &str| toml::from_str::<BuffMenu>(toml).map_err(|e| e.to_string());
+        let err = read(r#"buffs = [{ name = "a", cost = 1, effects = { bonuses = ["hit=+1"] } }]"#)
+            .unwrap_err();
+        assert!(err.contains("unknown field `bonuses`"), "{err}");
+        let err = read(r#"buffs = [{ name = "a", cost = 1, bonus = ["hit=+1"] }]"#).unwrap_err();
+        assert!(err.contains("unknown field `bonus`"), "{err}");
+        let err = read(r#"buffs = [{ name = "a", effects = {} }]"#).unwrap_err();
+        assert!(err.contains("missing field `cost`"), "{err}");
+    }
+}
@@ -0,0 +1,430 @@
+use crate::target_config::TargetConfig;
+use crate::weapon_config::{
+    RollCharacteristic, ValueCharacteristic, WeaponConfig, reroll_name, roll_name,
+};
+use anyhow::{Result, bail};
+use aos_statshammer_core::RerollType;
+use clap::Args;
+use serde::Deserialize;
+use std::str::FromStr;
+
+// Command line overrides applied on top of the loaded weapon and target configs.
+// These can also be read from a file (e.g. for each buff given to `optimize`),
+// with the same syntax as on the command line: `bonus = ["hit=+1"]`.
+#[derive(Args, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Overrides {
+    /// Set a characteristic, e.g. `hit=4`, `target.save=3`, `target.ward=5`, `target.health=6` or `target.ethereal=true`
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    set: Vec<SetOverride>,
+
+    /// Add a bonus to a characteristic, e.g. `wound=+1` or `target.save=-1`
+    #[arg(long = "bonus", value_name = "KEY=AMOUNT")]
+    bonus: Vec<BonusOverride>,
+
+    /// Set the reroll for a roll, e.g. `hit=ones`, `wound=failed` or `target.save=any`
+    #[arg(long = "reroll", value_name = "KEY=TYPE")]
+    reroll: Vec<RerollOverride>,
+
+    /// Make a roll explode, e.g. `hit:6:1` (extra hits on 6+) or `wound:5`
+    #[arg(long = "explode", value_name = "KEY[:ON[:EXTRA]]")]
+    explode: Vec<ExplodeOverride>,
+}
+
+impl Overrides {
+    /// These overrides, followed by `other` (which wins where they both set something).
+    pub fn and(&self, other: &Overrides) -> Overrides {
+        let mut overrides = self.clone();
+        overrides.set.extend(other.set.iter().cloned());
+        overrides.bonus.extend(other.bonus.iter().cloned());
+        overrides.reroll.extend(other.reroll.iter().cloned());
+        overrides.explode.extend(other.explode.iter().cloned());
+        overrides
+    }
+
+    pub fn apply_to_weapon(&self, config: &mut WeaponConfig) -> Result<()> {
+        for o in &self.set {
+            match o.key {
+                Key::Weapon(to) => config.set_characteristic(to, parse_value(o)?),
+                Key::Save | Key::Ward | Key::Ethereal | Key::Health | Key::Models => {}
+            }
+        }
+        for o in &self.bonus {
+            if let Key::Weapon(to) = o.key {
+                config.add_bonus(
+                    to,
+                    o.amount,
+                    Some(format!("--bonus {}={:+}", o.key, o.amount)),
+                );
+            }
+        }
+        for o in &self.reroll {
+            if let Key::Weapon(to) = o.key {
+                config.set_reroll(
+                    roll_characteristic(to)?,
+                    o.reroll_type,
+                    Some(format!("--reroll {}={}", o.key, reroll_name(o.reroll_type))),
+                );
+            }
+        }
+        for o in &self.explode {
+            config.set_exploding(
+                o.to,
+                o.on,
+                false,
+                o.extra,
+                Some(format!(
+                    "--explode {}:{}:{}",
+                    roll_name(o.to),
+                    o.on,
+                    o.extra
+                )),
+            );
+        }
+        Ok(())
+    }
+
+    /// Apply the target overrides. Setting `target.save` will create a target
+    /// if there isn't one already, every other override requires one.
+    pub fn apply_to_target(&self, config: &mut Option<TargetConfig>) -> Result<()> {
+        for o in self.set.iter().filter(|o| o.key == Key::Save) {
+            let save = parse_value(o)?;
+            match config {
+                Some(config) => config.set_save(save),
+                None => *config = Some(TargetConfig::from_save(save)),
+            }
+        }
+        for o in &self.set {
+            match o.key {
+                Key::Ward => target_for(config, o.key)?.set_ward(parse_value(o)?),
+                Key::Ethereal => target_for(config, o.key)?.set_ethereal(parse_value(o)?),
+                Key::Health => target_for(config, o.key)?.set_health(parse_value(o)?),
+                Key::Models => target_for(config, o.key)?.set_models(parse_value(o)?),
+                Key::Weapon(_) | Key::Save => {}
+            }
+        }
+        for o in self.bonus.iter().filter(|o| o.key == Key::Save) {
+            target_for(config, o.key)?.add_bonus(o.amount);
+        }
+        for o in self.reroll.iter().filter(|o| o.key == Key::Save) {
+            target_for(config, o.key)?.set_reroll(o.reroll_type);
+        }
+        Ok(())
+    }
+}
+
+fn target_for(config: &mut Option<TargetConfig>, key: Key) -> Result<&mut TargetConfig> {
+    match config {
+        Some(config) => Ok(config),
+        None => {
+            bail!("Cannot override `{key}` without a target. Please use either --save or --target.")
+        }
+    }
+}
+
+fn parse_value<T>(o: &SetOverride) -> Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    o.value
+        .parse()
+        .map_err(|err| anyhow::anyhow!("Invalid value for `{}`: {err}", o.key))
+}
+
+fn roll_characteristic(to: ValueCharacteristic) -> Result<RollCharacteristic> {
+    match to {
+        ValueCharacteristic::Hit => Ok(RollCharacteristic::Hit),
+        ValueCharacteristic::Wound => Ok(RollCharacteristic::Wound),
+        _ => bail!("`{}` cannot be rerolled", Key::Weapon(to)),
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Key {
+    Weapon(ValueCharacteristic),
+    Save,
+    Ward,
+    Ethereal,
+    Health,
+    Models,
+}
+
+impl FromStr for Key {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "attacks" | "attack" => Ok(Key::Weapon(ValueCharacteristic::Attacks)),
+            "hit" => Ok(Key::Weapon(ValueCharacteristic::Hit)),
+            "wound" => Ok(Key::Weapon(ValueCharacteristic::Wound)),
+            "rend" => Ok(Key::Weapon(ValueCharacteristic::Rend)),
+            "damage" => Ok(Key::Weapon(ValueCharacteristic::Damage)),
+            "target.save" => Ok(Key::Save),
+            "target.ward" => Ok(Key::Ward),
+            "target.ethereal" => Ok(Key::Ethereal),
+            "target.health" => Ok(Key::Health),
+            "target.models" => Ok(Key::Models),
+            other => Err(format!(
+                "unknown key `{other}` (expected one of: attacks, hit, wound, rend, damage, \
+                 target.save, target.ward, target.ethereal, target.health, target.models)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Key::Weapon(ValueCharacteristic::Attacks) => "attacks",
+            Key::Weapon(ValueCharacteristic::Hit) => "hit",
+            Key::Weapon(ValueCharacteristic::Wound) => "wound",
+            Key::Weapon(ValueCharacteristic::Rend) => "rend",
+            Key::Weapon(ValueCharacteristic::Damage) => "damage",
+            Key::Save => "target.save",
+            Key::Ward => "target.ward",
+            Key::Ethereal => "target.ethereal",
+            Key::Health => "target.health",
+            Key::Models => "target.models",
+        };
+        f.write_str(name)
+    }
+}
+
+fn split_assignment(s: &str) -> Result<(Key, &str), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{s}`"))?;
+    Ok((key.parse()?, value.trim()))
+}
+
+/// Parse each override from a string in a file, as on the command line.
+macro_rules! impl_try_from_string {
+    ($($t: ty),*) => {
+        $(
+            impl TryFrom<String> for $t {
+                type Error = String;
+                fn try_from(s: String) -> Result<Self, Self::Error> {
+                    s.parse()
+                }
+            }
+        )*
+    };
+}
+impl_try_from_string!(SetOverride, BonusOverride, RerollOverride, ExplodeOverride);
+
+/// The value is validated once the type for the key is known (see [`parse_value`]).
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+struct SetOverride {
+    key: Key,
+    value: String,
+}
+
+impl FromStr for SetOverride {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = split_assignment(s)?;
+        Ok(Self {
+            key,
+            value: value.to_string(),
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+struct BonusOverride {
+    key: Key,
+    amount: i16,
+}
+
+impl FromStr for BonusOverride {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = split_assignment(s)?;
+        if matches!(key, Key::Ward | Key::Ethereal | Key::Health | Key::Models) {
+            return Err(format!("`{key}` does not accept a bonus"));
+        }
+        let amount = value
+            .parse()
+            .map_err(|_| format!("invalid bonus `{value}` (expected e.g. +1 or -1)"))?;
+        Ok(Self { key, amount })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+struct RerollOverride {
+    key: Key,
+    reroll_type: RerollType,
+}
+
+impl FromStr for RerollOverride {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = split_assignment(s)?;
+        match key {
+            Key::Weapon(ValueCharacteristic::Hit | ValueCharacteristic::Wound) | Key::Save => {}
+            _ => return Err(format!("`{key}` cannot be rerolled")),
+        }
+        let reroll_type = match value {
+            "ones" => RerollType::Ones,
+            "failed" => RerollType::Failed,
+            "any" => RerollType::Any,
+            _ => {
+                return Err(format!(
+                    "invalid reroll `{value}` (expected one of: ones, failed, any)"
+                ));
+            }
+        };
+        Ok(Self { key, reroll_type })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(try_from = "String")]
+struct ExplodeOverride {
+    to: RollCharacteristic,
+    on: u8,
+    extra: u8,
+}
+
+impl FromStr for ExplodeOverride {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':').map(str::trim);
+        let to = match parts.next() {
+            Some("hit") => RollCharacteristic::Hit,
+            Some("wound") => RollCharacteristic::Wound,
+            _ => return Err(format!("expected `hit` or `wound` to explode, got `{s}`")),
+        };
+        let mut number = |name: &str, default: u8| match parts.next() {
+            Some(value) => value
+                .parse::<u8>()
+                .map_err(|_| format!("invalid {name} `{value}` in `{s}`")),
+            None => Ok(default),
+        };
+        let on = number("roll", 6)?;
+        let extra = number("extra", 1)?;
+        if parts.next().is_some() {
+            return Err(format!("expected KEY[:ON[:EXTRA]], got `{s}`"));
+        }
+        Ok(Self { to, on, extra })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weapon_config() -> WeaponConfig {
+        toml::from_str(
+            r#"
+            [characteristics]
+            attack = 2
+            hit = 3
+            wound = 4
+            rend = 0
+            damage = 1
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn overrides(args: &[&str]) -> Overrides {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Test {
+            #[command(flatten)]
+            overrides: Overrides,
+        }
+        let args = std::iter::once("test").chain(args.iter().copied());
+        Test::try_parse_from(args).unwrap().overrides
+    }
+
+    #[test]
+    fn parse_explode_defaults() {
+        let o: ExplodeOverride = "wound".parse().unwrap();
+        assert_eq!((o.to, o.on, o.extra), (RollCharacteristic::Wound, 6, 1));
+        let o: ExplodeOverride = "hit:5:2".parse().unwrap();
+        assert_eq!((o.to, o.on, o.extra), (RollCharacteristic::Hit, 5, 2));
+        assert!("rend:6".parse::<ExplodeOverride>().is_err());
+        assert!("hit:6:1:1".parse::<ExplodeOverride>().is_err());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_keys() {
+        assert!("toughness=4".parse::<SetOverride>().is_err());
+        assert!("damage=ones".parse::<RerollOverride>().is_err());
+        assert!("target.ward=+1".parse::<BonusOverride>().is_err());
+    }
+
+    #[test]
+    fn apply_weapon_overrides() {
+        let mut config = weapon_config();
+        overrides(&[
+            "--set",
+            "hit=4",
+            "--bonus",
+            "wound=+1",
+            "--reroll",
+            "hit=ones",
+            "--explode",
+            "hit:6:1",
+        ])
+        .apply_to_weapon(&mut config)
+        .unwrap();
+        let weapon: aos_statshammer_core::weapon::Weapon = config.try_into().unwrap();
+        assert_eq!(weapon.hit.value, 4);
+        assert_eq!(weapon.wound.bonus, 1);
+        assert_eq!(weapon.hit.reroll, Some(RerollType::Ones));
+        assert_eq!(weapon.hit.exploding.map(|e| (e.on, e.extra)), Some((6, 1)));
+    }
+
+    #[test]
+    fn apply_target_overrides() {
+        let mut config = None;
+        overrides(&[
+            "--set",
+            "target.save=4",
+            "--set",
+            "target.ward=5",
+            "--set",
+            "target.health=6",
+        ])
+        .apply_to_target(&mut config)
+        .unwrap();
+        let target: aos_statshammer_core::target::Target = config.unwrap().try_into().unwrap();
+        assert_eq!(target.save.value, 4);
+        assert_eq!(target.ward, Some(5));
+        assert_eq!(target.health, 6);
+    }
+
+    #[test]
+    fn read_from_a_file() {
+        let file: Overrides = toml::from_str(
+            r#"
+            bonus = ["hit=+1"]
+            reroll = ["wound=ones"]
+            "#,
+        )
+        .unwrap();
+        let mut config = weapon_config();
+        overrides(&["--bonus", "hit=+1"])
+            .and(&file)
+            .apply_to_weapon(&mut config)
+            .unwrap();
+        let weapon: aos_statshammer_core::weapon::Weapon = config.try_into().unwrap();
+        assert_eq!(weapon.hit.bonus, 2);
+        assert_eq!(weapon.wound.reroll, Some(RerollType::Ones));
+        assert!(toml::from_str::<Overrides>(r#"bonus = ["target.ward=+1"]"#).is_err());
+    }
+
+    #[test]
+    fn target_overrides_require_a_target() {
+        let mut config = None;
+        let result = overrides(&["--set", "target.ward=5"]).apply_to_target(&mut config);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use crate::chart::escape;
+use crate::export_chart::{distribution_svg, saves_svg};
+use crate::format::Formats;
+use crate::histogram::values_to_show;
+use crate::input::{TargetArgs, load_weapons};
+use crate::overrides::Overrides;
+use anyhow::Result;
+use aos_statshammer_core::RerollType;
+use aos_statshammer_core::target::{Save, Target};
+use aos_statshammer_core::weapon::Weapon;
+use clap::Args;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em auto; max-width: 900px; color: #222; }
+h1, h2 { border-bottom: 2px solid #8b0000; padding-bottom: 0.2em; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: center; }
+th { background: #8b0000; color: white; }
+td.name { text-align: left; font-weight: bold; }
+code { display: block; background: #f4f4f4; padding: 0.8em; white-space: pre-wrap; }
+svg { max-width: 100%; height: auto; }
+";
+
+#[derive(Args, Debug)]
+pub(crate) struct ReportArgs {
+    /// The weapon files to include in the report
+    #[arg(short, long, value_name = "FILE", required = true)]
+    weapon: Vec<PathBuf>,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// Where to write the HTML report
+    #[arg(long, value_name = "FILE")]
+    html: PathBuf,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl ReportArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let weapons = load_weapons(&self.weapon, &self.overrides, formats)?;
+        let target = self.target.load(&self.overrides, formats)?;
+        let invocation = invocation(std::env::args());
+        fs::write(&self.html, render(&weapons, &target, &invocation))?;
+        Ok(())
+    }
+}
+
+/// Render the full HTML report.
+pub(crate) fn render(weapons: &[(String, Weapon)], target: &Target, invocation: &str) -> String {
+    let mut html = String::new();
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>AOS Statshammer Report</title>\n<style>{STYLE}</style>\n</head>\n<body>"
+    )
+    .unwrap();
+    writeln!(html, "<h1>AOS Statshammer Report</h1>").unwrap();
+
+    writeln!(html, "<h2>Weapons</h2>").unwrap();
+    html.push_str(&weapons_table(weapons));
+    writeln!(html, "<h2>Target</h2>").unwrap();
+    html.push_str(&target_table(target));
+
+    writeln!(html, "<h2>Average Damage by Save</h2>").unwrap();
+    html.push_str(&save_table(weapons, target));
+    html.push_str(&saves_svg(weapons, target));
+
+    writeln!(html, "<h2>Damage Distribution vs {}</h2>", target.save).unwrap();
+    html.push_str(&distribution_svg(weapons, target));
+    for (name, weapon) in weapons {
+        writeln!(html, "<h3>{}</h3>", escape(name)).unwrap();
+        html.push_str(&distribution_table(weapon, target));
+    }
+
+    writeln!(
+        html,
+        "<h2>Invocation</h2>\n<code>{}</code>",
+        escape(invocation)
+    )
+    .unwrap();
+    writeln!(html, "</body>\n</html>").unwrap();
+    html
+}
+
+fn weapons_table(weapons: &[(String, Weapon)]) -> String {
+    let mut html = String::from(
+        "<table>\n<tr><th>Weapon</th><th>Atk</th><th>Hit</th><th>Wnd</th>\
+         <th>Rnd</th><th>Dmg</th><th>Abilities</th></tr>\n",
+    );
+    for (name, weapon) in weapons {
+        writeln!(
+            html,
+            "<tr><td class=\"name\">{}</td><td>{}</td><td>{}+</td><td>{}+</td>\
+             <td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(name),
+            weapon.attacks.value,
+            weapon.hit.value,
+            weapon.wound.value,
+            format_rend(weapon.rend.value),
+            weapon.damage.value,
+            escape(&weapon_abilities(weapon).join(", ")),
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn target_table(target: &Target) -> String {
+    let mut abilities = vec![];
+    if target.save.bonus != 0 {
+        abilities.push(format!("{:+} to save", target.save.bonus));
+    }
+    if let Some(reroll) = target.save.reroll {
+        abilities.push(format!("Reroll {} saves", format_reroll(reroll)));
+    }
+    if target.ethereal {
+        abilities.push("Ethereal".to_string());
+    }
+    format!(
+        "<table>\n<tr><th>Save</th><th>Ward</th><th>Abilities</th></tr>\n\
+         <tr><td>{}</td><td>{}</td><td>{}</td></tr>\n</table>\n",
+        target.save,
+        target.ward.map_or("-".to_string(), |w| format!("{w}+")),
+        escape(&abilities.join(", ")),
+    )
+}
+
+fn save_table(weapons: &[(String, Weapon)], target: &Target) -> String {
+    let mut html = String::from("<table>\n<tr><th>Weapon</th>");
+    for save in Save::ALL {
+        write!(html, "<th>{}</th>", Save::from(save)).unwrap();
+    }
+    html.push_str("</tr>\n");
+    for (name, weapon) in weapons {
+        write!(html, "<tr><td class=\"name\">{}</td>", escape(name)).unwrap();
+        for (_, damage) in weapon.save_table(target) {
+            write!(html, "<td>{damage:.3}</td>").unwrap();
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn distribution_table(weapon: &Weapon, target: &Target) -> String {
+    let dist = weapon.damage_distribution(target);
+    let mut html = String::from("<table>\n<tr><th>Damage</th>");
+    let values = values_to_show(&dist);
+    for k in &values {
+        write!(html, "<th>{k}</th>").unwrap();
+    }
+    html.push_str("</tr>\n<tr><th>P(=k)</th>");
+    for k in &values {
+        write!(html, "<td>{:.1}%</td>", dist.probability(*k) * 100.0).unwrap();
+    }
+    html.push_str("</tr>\n<tr><th>P(&ge;k)</th>");
+    for k in &values {
+        write!(html, "<td>{:.1}%</td>", dist.at_least(*k) * 100.0).unwrap();
+    }
+    writeln!(
+        html,
+        "</tr>\n</table>\n<p>Mean: {:.3}, Median: {}</p>",
+        dist.mean(),
+        dist.median()
+    )
+    .unwrap();
+    html
+}
+
+fn weapon_abilities(weapon: &Weapon) -> Vec<String> {
+    let mut abilities = vec![];
+    let bonuses = [
+        ("attacks", weapon.attacks.bonus),
+        ("hit", weapon.hit.bonus),
+        ("wound", weapon.wound.bonus),
+        ("rend", weapon.rend.bonus),
+        ("damage", weapon.damage.bonus),
+    ];
+    for (name, bonus) in bonuses {
+        if bonus != 0 {
+            abilities.push(format!("{bonus:+} {name}"));
+        }
+    }
+    for (name, reroll) in [("hits", weapon.hit.reroll), ("wounds", weapon.wound.reroll)] {
+        if let Some(reroll) = reroll {
+            abilities.push(format!("Reroll {} {name}", format_reroll(reroll)));
+        }
+    }
+    for (name, exploding) in [
+        ("hit", weapon.hit.exploding),
+        ("wound", weapon.wound.exploding),
+    ] {
+        if let Some(ab) = exploding {
+            abilities.push(format!(
+                "{}{}+ to {name}: {} extra",
+                if ab.unmodified { "Unmodified " } else { "" },
+                ab.on,
+                ab.extra
+            ));
+        }
+    }
+    abilities
+}
+
+fn format_rend(rend: u8) -> String {
+    match rend {
+        0 => "-".to_string(),
+        rend => format!("-{rend}"),
+    }
+}
+
+fn format_reroll(reroll: RerollType) -> &'static str {
+    match reroll {
+        RerollType::Ones => "1s",
+        RerollType::Failed => "failed",
+        RerollType::Any => "any",
+    }
+}
+
+/// The command line used to run the CLI, quoted so it can be copied back into a shell.
+fn invocation(args: impl Iterator<Item = String>) -> String {
+    args.map(|arg| {
+        let safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:+,@%".contains(c);
+        if !arg.is_empty() && arg.chars().all(safe) {
+            arg
+        } else {
+            format!("'{}'", arg.replace('\'', r"'\''"))
+        }
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::weapon::WeaponBuilder;
+
+    #[test]
+    fn invocation_is_quoted() {
+        let args = ["cli", "-w", "my weapon.toml", "--set", "hit=4"].map(String::from);
+        assert_eq!(
+            invocation(args.into_iter()),
+            "cli -w 'my weapon.toml' --set hit=4"
+        );
+    }
+
+    #[test]
+    fn render_includes_every_section() {
+        let weapon = WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap();
+        let weapons = vec![("Sword & Board".to_string(), weapon)];
+        let html = render(&weapons, &Target::from(4), "cli report");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Sword &amp; Board"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(html.contains("<code>cli report</code>"));
+    }
+}
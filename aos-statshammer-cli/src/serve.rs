@@ -0,0 +1,496 @@
+use crate::format::Formats;
+use crate::input::label;
+use crate::summary::Stat;
+use crate::table::SaveTableOutput;
+use crate::target_config::TargetConfig;
+use crate::validate::{target_errors, weapon_errors};
+use crate::weapon_config::WeaponConfig;
+use anyhow::{Context, Result, bail};
+use aos_statshammer_core::report::DistributionReport;
+use aos_statshammer_core::target::{Save, Target};
+use aos_statshammer_core::weapon::Weapon;
+use clap::Args;
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Any error from loading a profile or running a calculation.
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(Args, Debug)]
+pub(crate) struct ServeArgs {
+    /// Speak line-delimited JSON-RPC 2.0 over stdin/stdout
+    #[arg(long, required = true)]
+    stdio: bool,
+}
+
+impl ServeArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let mut session = Session::new(formats.clone());
+        session.run(io::stdin().lock(), io::stdout().lock())
+    }
+}
+
+/// The profiles loaded by a client, which live as long as the process.
+struct Session {
+    formats: Formats,
+    weapons: BTreeMap<String, Weapon>,
+    targets: BTreeMap<String, Target>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    /// Requests without an id are notifications, which get no response
+    #[serde(default)]
+    id: Option<Value>,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(err: anyhow::Error) -> Self {
+        // Formatted the same way as the errors from the other subcommands
+        Self::new(SERVER_ERROR, format!("{err:#}"))
+    }
+}
+
+/// Where to load a profile from, either a file or an inline config.
+#[derive(Deserialize, Debug)]
+struct LoadParams {
+    /// The name to refer to the profile by [default: the file name]
+    name: Option<String>,
+    path: Option<PathBuf>,
+    config: Option<Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ProfileParams {
+    weapon: String,
+    #[serde(default)]
+    target: Option<String>,
+    #[serde(default)]
+    save: Option<u8>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TableParams {
+    weapons: Vec<String>,
+    #[serde(default)]
+    target: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+enum ProfileKind {
+    Weapon,
+    Target,
+}
+
+#[derive(Deserialize, Debug)]
+struct ValidateParams {
+    kind: ProfileKind,
+    path: Option<PathBuf>,
+    config: Option<Value>,
+}
+
+#[derive(Serialize, Debug)]
+struct ValidateResult {
+    valid: bool,
+    errors: Vec<String>,
+}
+
+impl Session {
+    fn new(formats: Formats) -> Self {
+        Self {
+            formats,
+            weapons: BTreeMap::new(),
+            targets: BTreeMap::new(),
+        }
+    }
+
+    /// Handle requests (one per line) until the input is closed.
+    fn run(&mut self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle(&line) {
+                writeln!(output, "{response}")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle a single request or a batch of them, returning the response (if
+    /// there is one). A batch gets an array of the responses to its requests.
+    fn handle(&mut self, line: &str) -> Option<Value> {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(err) => return Some(error_response(Value::Null, PARSE_ERROR, err.to_string())),
+        };
+        match value {
+            Value::Array(batch) if batch.is_empty() => Some(error_response(
+                Value::Null,
+                INVALID_REQUEST,
+                "A batch must contain at least one request",
+            )),
+            Value::Array(batch) => {
+                let responses: Vec<Value> = batch
+                    .into_iter()
+                    .filter_map(|value| self.handle_request(value))
+                    .collect();
+                // A batch of only notifications gets no response at all
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            value => self.handle_request(value),
+        }
+    }
+
+    fn handle_request(&mut self, value: Value) -> Option<Value> {
+        let id = value.get("id").cloned().unwrap_or(Value::Null);
+        let request: Request = match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(err) => return Some(error_response(id, INVALID_REQUEST, err.to_string())),
+        };
+        if request.jsonrpc != "2.0" {
+            return Some(error_response(
+                id,
+                INVALID_REQUEST,
+                "jsonrpc must be \"2.0\"",
+            ));
+        }
+        debug!("{} {}", request.method, request.params);
+        let result = self.call(&request.method, request.params);
+        let id = request.id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_response(id, err.code, err.message),
+        })
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "loadWeapon" => {
+                let params: LoadParams = parse_params(params)?;
+                let (name, config): (String, WeaponConfig) = self.load(params, "weapon")?;
+                self.weapons.insert(name.clone(), config.try_into()?);
+                Ok(json!({ "name": name }))
+            }
+            "loadTarget" => {
+                let params: LoadParams = parse_params(params)?;
+                let (name, config): (String, TargetConfig) = self.load(params, "target")?;
+                self.targets.insert(name.clone(), config.try_into()?);
+                Ok(json!({ "name": name }))
+            }
+            "profiles" => Ok(json!({
+                "weapons": self.weapons.keys().collect::<Vec<_>>(),
+                "targets": self.targets.keys().collect::<Vec<_>>(),
+            })),
+            "average" => {
+                let params: ProfileParams = parse_params(params)?;
+                let weapon = self.weapon(&params.weapon)?;
+                let target = self.profile_target(&params)?;
+                Ok(json!({
+                    "average_damage": weapon.average_damage(&target),
+                    "summary": weapon.damage_summary(&target),
+                }))
+            }
+            "distribution" => {
+                let params: ProfileParams = parse_params(params)?;
+                let weapon = self.weapon(&params.weapon)?;
+                let target = self.profile_target(&params)?;
+                let distribution = weapon.damage_distribution(&target);
+                Ok(to_value(&DistributionReport::from(&distribution)))
+            }
+            "table" => {
+                let params: TableParams = parse_params(params)?;
+                let weapons = params
+                    .weapons
+                    .iter()
+                    .map(|name| Ok((name.clone(), self.weapon(name)?.clone())))
+                    .collect::<Result<Vec<_>>>()?;
+                let target = match &params.target {
+                    Some(name) => self.target(name)?.clone(),
+                    None => Target::from(Save::NONE),
+                };
+                Ok(to_value(&SaveTableOutput::new(
+                    &weapons,
+                    &target,
+                    Stat::Mean,
+                )))
+            }
+            "validate" => {
+                let params: ValidateParams = parse_params(params)?;
+                let source = LoadParams {
+                    name: None,
+                    path: params.path,
+                    config: params.config,
+                };
+                let errors = match params.kind {
+                    ProfileKind::Weapon => {
+                        self.load(source, "weapon").map(|(_, c)| weapon_errors(c))
+                    }
+                    ProfileKind::Target => {
+                        self.load(source, "target").map(|(_, c)| target_errors(c))
+                    }
+                }
+                .unwrap_or_else(|err| vec![format!("{err:#}")]);
+                Ok(to_value(&ValidateResult {
+                    valid: errors.is_empty(),
+                    errors,
+                }))
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method: {method}"),
+            )),
+        }
+    }
+
+    /// Parse a config from a file (in the same way as the other subcommands)
+    /// or from an inline config.
+    fn load<T: DeserializeOwned>(&self, params: LoadParams, kind: &str) -> Result<(String, T)> {
+        match (params.path, params.config) {
+            (Some(path), None) => {
+                let config = self.formats.read(&path)?;
+                Ok((params.name.unwrap_or_else(|| label(&path)), config))
+            }
+            (None, Some(config)) => {
+                let name = params
+                    .name
+                    .with_context(|| format!("A name is required for an inline {kind} config"))?;
+                let config = serde_json::from_value(config)
+                    .with_context(|| format!("Unable to parse the {kind} config"))?;
+                Ok((name, config))
+            }
+            _ => bail!("Please give either a path or a config (but not both)"),
+        }
+    }
+
+    fn weapon(&self, name: &str) -> Result<&Weapon> {
+        self.weapons
+            .get(name)
+            .with_context(|| format!("No weapon named {name:?} has been loaded"))
+    }
+
+    fn target(&self, name: &str) -> Result<&Target> {
+        self.targets
+            .get(name)
+            .with_context(|| format!("No target named {name:?} has been loaded"))
+    }
+
+    fn profile_target(&self, params: &ProfileParams) -> Result<Target> {
+        match (&params.target, params.save) {
+            (Some(name), None) => Ok(self.target(name)?.clone()),
+            (None, Some(save)) => TargetConfig::from_save(save).try_into(),
+            (Some(_), Some(_)) => bail!("Please use either save or target (but not both)."),
+            (None, None) => bail!("No target specified. Please use either save or target."),
+        }
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("outputs always serialize")
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn request(session: &mut Session, method: &str, params: Value) -> Value {
+        let line = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        session.handle(&line.to_string()).unwrap()
+    }
+
+    fn example(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("examples")
+            .join(path)
+    }
+
+    #[test]
+    fn load_and_calculate() {
+        let mut session = Session::new(Formats::default());
+        let response = request(
+            &mut session,
+            "loadWeapon",
+            json!({ "path": example("weapons/simple.toml") }),
+        );
+        assert_eq!(response["result"]["name"], "simple");
+        let response = request(
+            &mut session,
+            "average",
+            json!({ "weapon": "simple", "save": 4 }),
+        );
+        let average = response["result"]["average_damage"].as_f64().unwrap();
+        assert!((average - 1.0 / 3.0).abs() < 1e-9);
+        let mean = response["result"]["summary"]["mean"].as_f64().unwrap();
+        assert!((mean - average).abs() < 1e-9);
+
+        let response = request(&mut session, "table", json!({ "weapons": ["simple"] }));
+        assert_eq!(response["result"]["saves"][0], "2+");
+        let response = request(
+            &mut session,
+            "distribution",
+            json!({ "weapon": "simple", "save": 4 }),
+        );
+        assert_eq!(response["result"]["summary"]["p50"], 0);
+    }
+
+    #[test]
+    fn average_without_a_summary_for_too_much_damage() {
+        let mut session = Session::new(Formats::default());
+        let config = json!({
+            "characteristics": { "attack": 255, "hit": 3, "wound": 3, "rend": 0, "damage": 50 },
+            "abilities": { "exploding": [
+                { "to": "hit", "on": 2, "extra": 20 },
+                { "to": "wound", "on": 2, "extra": 20 },
+            ] },
+        });
+        request(
+            &mut session,
+            "loadWeapon",
+            json!({ "name": "huge", "config": config }),
+        );
+        let response = request(
+            &mut session,
+            "average",
+            json!({ "weapon": "huge", "save": 4 }),
+        );
+        assert!(response["result"]["average_damage"].as_f64().unwrap() > 0.0);
+        assert_eq!(response["result"]["summary"], Value::Null);
+    }
+
+    #[test]
+    fn inline_configs() {
+        let mut session = Session::new(Formats::default());
+        let config = json!({ "characteristics": { "save": 4 }, "abilities": { "ward": 5 } });
+        let response = request(
+            &mut session,
+            "loadTarget",
+            json!({ "name": "warded", "config": config }),
+        );
+        assert_eq!(response["result"]["name"], "warded");
+        let response = request(&mut session, "profiles", Value::Null);
+        assert_eq!(response["result"]["targets"], json!(["warded"]));
+    }
+
+    #[test]
+    fn validate() {
+        let mut session = Session::new(Formats::default());
+        let config = json!({ "characteristics": { "save": 9 } });
+        let response = request(
+            &mut session,
+            "validate",
+            json!({ "kind": "target", "config": config }),
+        );
+        assert_eq!(response["result"]["valid"], false);
+        let response = request(
+            &mut session,
+            "validate",
+            json!({ "kind": "weapon", "path": "missing.toml" }),
+        );
+        // The rest of the message comes from the OS
+        let error = response["result"]["errors"][0].as_str().unwrap();
+        assert!(
+            error.starts_with("Unable to read missing.toml: "),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut session = Session::new(Formats::default());
+        let response = session.handle("{ not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        let response = request(&mut session, "nope", Value::Null);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        let response = request(&mut session, "average", json!({ "save": 4 }));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        let response = request(&mut session, "average", json!({ "weapon": "x", "save": 4 }));
+        assert_eq!(response["error"]["code"], SERVER_ERROR);
+        assert_eq!(
+            response["error"]["message"],
+            "No weapon named \"x\" has been loaded"
+        );
+    }
+
+    #[test]
+    fn batches() {
+        let mut session = Session::new(Formats::default());
+        let batch = json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "profiles" },
+            { "jsonrpc": "2.0", "method": "profiles" },
+            { "jsonrpc": "2.0", "id": 2, "method": "nope" },
+            "not a request",
+        ]);
+        let response = session.handle(&batch.to_string()).unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["error"]["code"], INVALID_REQUEST);
+
+        let notifications = json!([{ "jsonrpc": "2.0", "method": "profiles" }]);
+        assert!(session.handle(&notifications.to_string()).is_none());
+        let response = session.handle("[]").unwrap();
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[test]
+    fn notifications_get_no_response() {
+        let mut session = Session::new(Formats::default());
+        let line = json!({ "jsonrpc": "2.0", "method": "profiles" }).to_string();
+        assert!(session.handle(&line).is_none());
+
+        let input = format!(
+            "{line}\n\n{}\n",
+            json!({ "jsonrpc": "2.0", "id": 7, "method": "profiles" })
+        );
+        let mut output = vec![];
+        session.run(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("\"id\":7"));
+    }
+}
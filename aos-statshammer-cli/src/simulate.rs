@@ -0,0 +1,111 @@
+use crate::format::{Formats, Output, text_table};
+use crate::histogram::values_to_show;
+use crate::input::{TargetArgs, load_weapon};
+use crate::overrides::Overrides;
+use crate::summary::Stat;
+use anyhow::Result;
+use aos_statshammer_core::distribution::Summary;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Args, Debug)]
+pub(crate) struct SimulateArgs {
+    #[arg(short, long, value_name = "FILE")]
+    weapon: PathBuf,
+
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// The number of times to roll the attacks
+    #[arg(long, default_value_t = 10_000)]
+    trials: usize,
+
+    /// The random seed to use, so that results can be reproduced [default: random]
+    #[arg(long)]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl SimulateArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let weapon = load_weapon(&self.weapon, &self.overrides, formats)?;
+        let target = self.target.load(&self.overrides, formats)?;
+        let seed = self.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64)
+        });
+        let simulated = weapon.simulate_damage(&target, self.trials, seed);
+        let exact = weapon.damage_distribution(&target);
+        let values = values_to_show(&exact);
+        let output = SimulateOutput {
+            trials: self.trials,
+            seed,
+            simulated_mean: simulated.mean(),
+            exact_mean: exact.mean(),
+            simulated_summary: simulated.summary(),
+            exact_summary: exact.summary(),
+            simulated: values.iter().map(|&k| simulated.probability(k)).collect(),
+            exact: values.iter().map(|&k| exact.probability(k)).collect(),
+            damage: values,
+        };
+        print!("{}", formats.render(&output)?);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct SimulateOutput {
+    trials: usize,
+    seed: u64,
+    simulated_mean: f64,
+    exact_mean: f64,
+    simulated_summary: Summary,
+    exact_summary: Summary,
+    /// The damage values that `simulated` and `exact` refer to
+    damage: Vec<usize>,
+    simulated: Vec<f64>,
+    exact: Vec<f64>,
+}
+
+impl Output for SimulateOutput {
+    fn text(&self) -> String {
+        let mut text = format!(
+            "Simulated Average Damage: {:.3} (exact: {:.3}) from {} trials (seed {})\n\n",
+            self.simulated_mean, self.exact_mean, self.trials, self.seed
+        );
+        let headers = ["Damage", "Simulated", "Exact"].map(String::from);
+        let rows: Vec<Vec<String>> = self
+            .damage
+            .iter()
+            .zip(self.simulated.iter().zip(&self.exact))
+            .map(|(k, (simulated, exact))| {
+                vec![
+                    k.to_string(),
+                    format!("{:.1}%", simulated * 100.0),
+                    format!("{:.1}%", exact * 100.0),
+                ]
+            })
+            .collect();
+        text.push_str(&text_table(&headers, &rows));
+
+        let headers = ["Statistic", "Simulated", "Exact"].map(String::from);
+        let rows: Vec<Vec<String>> = Stat::value_variants()
+            .iter()
+            .map(|stat| {
+                vec![
+                    stat.name().to_string(),
+                    stat.format(&self.simulated_summary),
+                    stat.format(&self.exact_summary),
+                ]
+            })
+            .collect();
+        text.push('\n');
+        text.push_str(&text_table(&headers, &rows));
+        text
+    }
+}
@@ -0,0 +1,128 @@
+use aos_statshammer_core::distribution::Summary;
+use aos_statshammer_core::weapon::MAX_SUMMARY_DAMAGE;
+use clap::ValueEnum;
+
+/// The statistics besides the mean, which is usually shown on its own.
+const SPREAD: [Stat; 6] = [
+    Stat::StdDev,
+    Stat::Cv,
+    Stat::P10,
+    Stat::P50,
+    Stat::P90,
+    Stat::Zero,
+];
+
+/// The headers for the columns added by [`cells`].
+pub(crate) fn headers() -> impl Iterator<Item = String> {
+    SPREAD.iter().map(|stat| stat.name().to_string())
+}
+
+/// The statistics besides the mean, as cells of a table (or blank cells if there's
+/// no summary, for weapons which can do too much damage).
+pub(crate) fn cells(summary: Option<&Summary>) -> Vec<String> {
+    SPREAD
+        .iter()
+        .map(|stat| summary.map_or("-".to_string(), |summary| stat.format(summary)))
+        .collect()
+}
+
+/// The statistics besides the mean, one per line.
+pub(crate) fn text(summary: Option<&Summary>) -> String {
+    let Some(summary) = summary else {
+        return format!(
+            "The other statistics are only calculated for weapons which can do at most {MAX_SUMMARY_DAMAGE} damage\n"
+        );
+    };
+    format!(
+        "Std Dev: {}\nCoefficient of Variation: {}\nPercentiles (10th/50th/90th): {} / {} / {}\nP(0 Damage): {}\n",
+        Stat::StdDev.format(summary),
+        Stat::Cv.format(summary),
+        summary.p10,
+        summary.p50,
+        summary.p90,
+        Stat::Zero.format(summary),
+    )
+}
+
+/// A single statistic, to show in each cell of a table.
+#[derive(ValueEnum, Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub(crate) enum Stat {
+    /// The average
+    #[default]
+    Mean,
+    /// The standard deviation
+    StdDev,
+    /// The coefficient of variation (the standard deviation relative to the mean)
+    Cv,
+    /// The 10th percentile
+    P10,
+    /// The median
+    P50,
+    /// The 90th percentile
+    P90,
+    /// The probability of no damage at all
+    Zero,
+}
+
+impl Stat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::Mean => "Mean",
+            Stat::StdDev => "Std Dev",
+            Stat::Cv => "CV",
+            Stat::P10 => "P10",
+            Stat::P50 => "P50",
+            Stat::P90 => "P90",
+            Stat::Zero => "P(0)",
+        }
+    }
+
+    pub fn format(&self, summary: &Summary) -> String {
+        match self {
+            Stat::Mean => format!("{:.3}", summary.mean),
+            Stat::StdDev => format!("{:.3}", summary.std_dev),
+            Stat::Cv => summary
+                .coefficient_of_variation
+                .map_or("-".to_string(), |cv| format!("{cv:.3}")),
+            Stat::P10 => summary.p10.to_string(),
+            Stat::P50 => summary.p50.to_string(),
+            Stat::P90 => summary.p90.to_string(),
+            Stat::Zero => format!("{:.1}%", summary.zero * 100.0),
+        }
+    }
+
+    /// Like [`Stat::format`], where there may be no summary (for weapons which can do
+    /// too much damage) but the mean is always known.
+    pub fn format_or_mean(&self, summary: Option<&Summary>, mean: f64) -> String {
+        match (summary, self) {
+            (Some(summary), _) => self.format(summary),
+            (None, Stat::Mean) => format!("{mean:.3}"),
+            (None, _) => "-".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::distribution::Distribution;
+
+    #[test]
+    fn no_damage_has_no_cv() {
+        let summary = Distribution::certain(0).summary();
+        assert_eq!(
+            cells(Some(&summary)),
+            ["0.000", "-", "0", "0", "0", "100.0%"]
+        );
+        assert_eq!(headers().count(), cells(Some(&summary)).len());
+    }
+
+    #[test]
+    fn only_the_mean_without_a_summary() {
+        assert_eq!(cells(None), ["-"; 6]);
+        assert_eq!(Stat::Mean.format_or_mean(None, 1.5), "1.500");
+        assert_eq!(Stat::P50.format_or_mean(None, 1.5), "-");
+        let summary = Distribution::certain(2).summary();
+        assert_eq!(Stat::P50.format_or_mean(Some(&summary), 2.0), "2");
+    }
+}
@@ -0,0 +1,98 @@
+use crate::format::{Formats, Output, text_table};
+use crate::input::{TargetArgs, load_weapons};
+use crate::overrides::Overrides;
+use crate::summary::Stat;
+use anyhow::Result;
+use aos_statshammer_core::report::SaveTableRow;
+use aos_statshammer_core::target::{Save, Target};
+use aos_statshammer_core::weapon::Weapon;
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub(crate) struct TableArgs {
+    /// The weapon files to include (one row each)
+    #[arg(short, long, value_name = "FILE", required = true)]
+    weapon: Vec<PathBuf>,
+
+    // Only the target's modifiers are used, as the save is replaced in every column
+    #[command(flatten)]
+    target: TargetArgs,
+
+    /// The statistic to show for each save (every one is in the JSON output)
+    #[arg(long, value_enum, value_name = "STAT", default_value_t)]
+    stat: Stat,
+
+    #[command(flatten)]
+    overrides: Overrides,
+}
+
+impl TableArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let weapons = load_weapons(&self.weapon, &self.overrides, formats)?;
+        let target = self
+            .target
+            .load_or(Target::from(Save::NONE), &self.overrides, formats)?;
+        print!(
+            "{}",
+            formats.render(&SaveTableOutput::new(&weapons, &target, self.stat))?
+        );
+        Ok(())
+    }
+}
+
+/// The average damage of each weapon against every save.
+#[derive(Serialize, Debug)]
+pub(crate) struct SaveTableOutput {
+    saves: Vec<String>,
+    weapons: Vec<WeaponRows>,
+    #[serde(skip)]
+    stat: Stat,
+}
+
+#[derive(Serialize, Debug)]
+struct WeaponRows {
+    name: String,
+    rows: Vec<SaveTableRow>,
+}
+
+impl SaveTableOutput {
+    pub fn new(weapons: &[(String, Weapon)], target: &Target, stat: Stat) -> Self {
+        Self {
+            saves: Save::ALL
+                .iter()
+                .map(|&s| Save::from(s).to_string())
+                .collect(),
+            weapons: weapons
+                .iter()
+                .map(|(name, weapon)| WeaponRows {
+                    name: name.clone(),
+                    rows: SaveTableRow::for_weapon(weapon, target),
+                })
+                .collect(),
+            stat,
+        }
+    }
+}
+
+impl Output for SaveTableOutput {
+    fn text(&self) -> String {
+        let headers: Vec<String> = std::iter::once("Weapon".to_string())
+            .chain(self.saves.iter().cloned())
+            .collect();
+        let rows: Vec<Vec<String>> = self
+            .weapons
+            .iter()
+            .map(|row| {
+                std::iter::once(row.name.clone())
+                    .chain(row.rows.iter().map(|r| {
+                        self.stat
+                            .format_or_mean(r.summary.as_ref(), r.average_damage)
+                    }))
+                    .collect()
+            })
+            .collect();
+        text_table(&headers, &rows)
+    }
+}
@@ -1,24 +1,102 @@
-use crate::rerolls::RerollType;
+use crate::serde_utils::*;
 use aos_statshammer_core as core;
+use aos_statshammer_core::RerollType;
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub(crate) struct TargetConfig {
+    /// The number of models in the target unit.
+    #[serde(default = "default_u32::<1>")]
+    models: u32,
+    /// The points cost of the whole target unit.
+    points: Option<u32>,
     characteristics: Characteristics,
     #[serde(default)]
     abilities: Abilities,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub(crate) struct Characteristics {
     save: u8,
+    #[serde(default = "default_u32::<1>")]
+    health: u32,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default)]
 pub(crate) struct Abilities {
     bonus: Option<i16>,
+    #[serde(default)]
     ethereal: bool,
     reroll: Option<RerollType>,
+    ward: Option<u8>,
+}
+
+impl TargetConfig {
+    pub fn from_save(save: u8) -> Self {
+        Self {
+            models: 1,
+            points: None,
+            characteristics: Characteristics { save, health: 1 },
+            abilities: Default::default(),
+        }
+    }
+
+    /// Any values which are out of range (these are not errors when converting,
+    /// but are almost certainly a mistake).
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if !(1..=core::target::Save::NONE).contains(&self.characteristics.save) {
+            problems.push(format!(
+                "save should be between 1 and {} (for no save), got {}",
+                core::target::Save::NONE,
+                self.characteristics.save
+            ));
+        }
+        problems.extend(
+            self.abilities
+                .ward
+                .and_then(|ward| core::validate::ward_problem("ward", ward)),
+        );
+        if self.characteristics.health == 0 {
+            problems.push("health should be at least 1".to_string());
+        }
+        if self.models == 0 {
+            problems.push("models should be at least 1".to_string());
+        }
+        problems
+    }
+
+    pub fn points(&self) -> Option<u32> {
+        self.points
+    }
+
+    pub fn set_save(&mut self, save: u8) {
+        self.characteristics.save = save;
+    }
+
+    pub fn set_ward(&mut self, ward: u8) {
+        self.abilities.ward = Some(ward);
+    }
+
+    pub fn set_health(&mut self, health: u32) {
+        self.characteristics.health = health;
+    }
+
+    pub fn set_models(&mut self, models: u32) {
+        self.models = models;
+    }
+
+    pub fn set_ethereal(&mut self, ethereal: bool) {
+        self.abilities.ethereal = ethereal;
+    }
+
+    pub fn add_bonus(&mut self, amount: i16) {
+        self.abilities.bonus = Some(self.abilities.bonus.unwrap_or_default() + amount);
+    }
+
+    pub fn set_reroll(&mut self, reroll: RerollType) {
+        self.abilities.reroll = Some(reroll);
+    }
 }
 
 impl TryInto<core::target::Target> for TargetConfig {
@@ -27,13 +105,40 @@ impl TryInto<core::target::Target> for TargetConfig {
         let mut builder = core::target::TargetBuilder::default();
         builder
             .save(self.characteristics.save)
+            .health(self.characteristics.health)
+            .models(self.models)
             .ethereal(self.abilities.ethereal);
         if let Some(bonus) = self.abilities.bonus {
             builder.bonus(bonus);
         }
         if let Some(reroll) = self.abilities.reroll {
-            builder.reroll(reroll.into());
+            builder.reroll(reroll);
+        }
+        if let Some(ward) = self.abilities.ward {
+            builder.ward(ward);
         }
         builder.build().map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ward_must_be_possible_to_fail() {
+        for ward in [0, 1, 7] {
+            let mut config = TargetConfig::from_save(4);
+            config.set_ward(ward);
+            assert_eq!(
+                config.problems(),
+                vec![format!("ward must be between 2 and 6 (got {ward})")]
+            );
+        }
+        let mut config = TargetConfig::from_save(4);
+        config.set_ward(6);
+        assert!(config.problems().is_empty());
+        let target: core::target::Target = config.try_into().unwrap();
+        assert_eq!(target.ward, Some(6));
+    }
+}
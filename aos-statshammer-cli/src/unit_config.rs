@@ -0,0 +1,82 @@
+use crate::serde_utils::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A unit, made up of a number of models and the weapons they carry.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct UnitConfig {
+    pub name: String,
+    #[serde(default = "default_u32::<1>")]
+    pub models: u32,
+    /// The points cost of the whole unit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<u32>,
+    #[serde(default)]
+    pub weapons: Vec<UnitWeapon>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct UnitWeapon {
+    /// The weapon config file, relative to the unit file.
+    pub file: PathBuf,
+    /// How many models use this weapon (every model in the unit when not given).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<u32>,
+}
+
+impl UnitConfig {
+    /// Any values which are out of range.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+        if self.models == 0 {
+            problems.push("models should be at least 1".to_string());
+        }
+        for weapon in &self.weapons {
+            match weapon.models {
+                Some(0) => problems.push(format!(
+                    "{}: models should be at least 1",
+                    weapon.file.display()
+                )),
+                Some(models) if models > self.models => problems.push(format!(
+                    "{}: used by {models} models, but the unit only has {}",
+                    weapon.file.display(),
+                    self.models
+                )),
+                _ => {}
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_default_to_one() {
+        let config: UnitConfig = toml::from_str(
+            r#"
+            name = "Liberators"
+            weapons = [{ file = "liberators/warhammer.toml" }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.models, 1);
+        assert_eq!(config.weapons[0].models, None);
+        assert!(config.problems().is_empty());
+    }
+
+    #[test]
+    fn problems_with_too_many_models() {
+        let config: UnitConfig = toml::from_str(
+            r#"
+            name = "Liberators"
+            models = 5
+            weapons = [{ file = "liberators/grandhammer.toml", models = 6 }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.problems().len(), 1);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::format::{Formats, Output};
+use crate::target_config::TargetConfig;
+use crate::unit_config::UnitConfig;
+use crate::weapon_config::WeaponConfig;
+use anyhow::{Result, bail};
+use aos_statshammer_core::target::Target;
+use aos_statshammer_core::weapon::Weapon;
+use clap::{ArgGroup, Args};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("files").required(true).multiple(true).args(["weapon", "target", "unit"])))]
+pub(crate) struct ValidateArgs {
+    /// Weapon files to validate
+    #[arg(short, long, value_name = "FILE")]
+    weapon: Vec<PathBuf>,
+
+    /// Target files to validate
+    #[arg(short, long, value_name = "FILE")]
+    target: Vec<PathBuf>,
+
+    /// Unit files to validate (including the weapon files they refer to)
+    #[arg(short, long, value_name = "FILE")]
+    unit: Vec<PathBuf>,
+}
+
+impl ValidateArgs {
+    pub fn execute(&self, formats: &Formats) -> Result<()> {
+        let mut files = vec![];
+        for path in &self.weapon {
+            files.push(FileResult::new(
+                path,
+                "weapon",
+                validate_weapon(path, formats),
+            ));
+        }
+        for path in &self.target {
+            files.push(FileResult::new(
+                path,
+                "target",
+                validate_target(path, formats),
+            ));
+        }
+        for path in &self.unit {
+            files.push(FileResult::new(path, "unit", validate_unit(path, formats)));
+        }
+        let invalid = files.iter().filter(|f| !f.errors.is_empty()).count();
+        let output = ValidateOutput {
+            valid: invalid == 0,
+            files,
+        };
+        print!("{}", formats.render(&output)?);
+        if invalid > 0 {
+            bail!("{invalid} file(s) are invalid");
+        }
+        Ok(())
+    }
+}
+
+fn validate_weapon(path: &Path, formats: &Formats) -> Vec<String> {
+    match formats.read(path) {
+        Ok(config) => weapon_errors(config),
+        Err(err) => vec![format!("{err:#}")],
+    }
+}
+
+fn validate_target(path: &Path, formats: &Formats) -> Vec<String> {
+    match formats.read(path) {
+        Ok(config) => target_errors(config),
+        Err(err) => vec![format!("{err:#}")],
+    }
+}
+
+fn validate_unit(path: &Path, formats: &Formats) -> Vec<String> {
+    let read = formats
+        .resolve(path)
+        .and_then(|path| Ok((formats.read::<UnitConfig>(&path)?, path)));
+    let (config, path) = match read {
+        Ok(read) => read,
+        Err(err) => return vec![format!("{err:#}")],
+    };
+    let mut errors = config.problems();
+    // Weapon files are relative to the unit file, wherever a `catalog:` reference puts it
+    let dir = path.parent().unwrap_or(Path::new(""));
+    for weapon in &config.weapons {
+        let weapon_path = dir.join(&weapon.file);
+        for error in validate_weapon(&weapon_path, formats) {
+            errors.push(format!("{}: {error}", weapon.file.display()));
+        }
+    }
+    errors
+}
+
+/// Every problem with a weapon config, including any error converting it.
+pub(crate) fn weapon_errors(config: WeaponConfig) -> Vec<String> {
+    let mut errors = config.problems();
+    if let Err(err) = TryInto::<Weapon>::try_into(config) {
+        errors.push(format!("{err:#}"));
+    }
+    errors
+}
+
+/// Every problem with a target config, including any error converting it.
+pub(crate) fn target_errors(config: TargetConfig) -> Vec<String> {
+    let mut errors = config.problems();
+    if let Err(err) = TryInto::<Target>::try_into(config) {
+        errors.push(format!("{err:#}"));
+    }
+    errors
+}
+
+#[derive(Serialize, Debug)]
+struct ValidateOutput {
+    valid: bool,
+    files: Vec<FileResult>,
+}
+
+#[derive(Serialize, Debug)]
+struct FileResult {
+    path: PathBuf,
+    kind: &'static str,
+    errors: Vec<String>,
+}
+
+impl FileResult {
+    fn new(path: &Path, kind: &'static str, errors: Vec<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            kind,
+            errors,
+        }
+    }
+}
+
+impl Output for ValidateOutput {
+    fn text(&self) -> String {
+        let mut text = String::new();
+        for file in &self.files {
+            let status = if file.errors.is_empty() { "✓" } else { "✗" };
+            text.push_str(&format!(
+                "{status} {} ({})\n",
+                file.path.display(),
+                file.kind
+            ));
+            for error in &file.errors {
+                for (i, line) in error.lines().enumerate() {
+                    let bullet = if i == 0 { "-" } else { " " };
+                    text.push_str(&format!("    {bullet} {line}\n"));
+                }
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_from_the_catalog_find_their_weapons() {
+        let formats = Formats {
+            catalog: Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/catalog")).into(),
+            ..Formats::default()
+        };
+        let errors = validate_unit(Path::new("catalog:stormcast/liberators"), &formats);
+        assert!(errors.is_empty(), "{errors:?}");
+        let errors = validate_unit(Path::new("catalog:stormcast/unknown"), &formats);
+        assert_eq!(errors.len(), 1);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::avg::AvgArgs;
+use crate::format::Formats;
+use anyhow::{Context, Result};
+use log::{debug, error};
+use notify::{Event, RecursiveMode, Watcher};
+use similar::{ChangeTag, TextDiff};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Editors tend to emit a burst of events for a single save, so wait for
+/// things to settle before re-running.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watch the input files of `args`, re-rendering the output every time one of
+/// them changes. Errors are reported inline and never end the watch.
+pub(crate) fn run(args: &AvgArgs, formats: &Formats) -> Result<()> {
+    let files = args
+        .input_files()?
+        .iter()
+        .map(|f| absolute(&formats.resolve(f)?))
+        .collect::<Result<Vec<_>>>()?;
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for dir in watched_dirs(&files) {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Unable to watch {}", dir.display()))?;
+    }
+
+    let mut previous = render(args, formats, None);
+    loop {
+        let event = rx.recv()?;
+        let mut changed = relevant_paths(event, &files);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            changed.extend(relevant_paths(event, &files));
+        }
+        if changed.is_empty() {
+            continue;
+        }
+        changed.sort();
+        changed.dedup();
+        for path in &changed {
+            println!("Changed: {}", path.display());
+        }
+        if let Some(output) = render(args, formats, previous.as_deref()) {
+            previous = Some(output);
+        }
+    }
+}
+
+/// Render and print the output, along with a diff against `previous` (if any).
+/// Returns the new output, or `None` if it could not be produced.
+fn render(args: &AvgArgs, formats: &Formats, previous: Option<&str>) -> Option<String> {
+    match args.render(formats) {
+        Ok(output) => {
+            print!("{output}");
+            if let Some(previous) = previous {
+                print!("{}", diff(previous, &output));
+            }
+            println!();
+            Some(output)
+        }
+        Err(err) => {
+            error!("{err:#}");
+            None
+        }
+    }
+}
+
+/// The lines which changed between `previous` and `current`.
+fn diff(previous: &str, current: &str) -> String {
+    let diff = TextDiff::from_lines(previous, current);
+    if diff.ratio() == 1.0 {
+        return "(no change from previous output)\n".to_string();
+    }
+    let mut text = "Changes from previous output:\n".to_string();
+    for change in diff.iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Delete => "-",
+            ChangeTag::Insert => "+",
+            ChangeTag::Equal => continue,
+        };
+        text.push_str(&format!("{sign} {change}"));
+    }
+    text
+}
+
+/// The directories to watch for changes to `files`. These are the parent
+/// directories rather than the files themselves, as a lot of editors save by
+/// replacing the file (which would drop the watch).
+fn watched_dirs(files: &[PathBuf]) -> Vec<&Path> {
+    let mut dirs: Vec<&Path> = files.iter().filter_map(|f| f.parent()).collect();
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+fn relevant_paths(event: notify::Result<Event>, files: &[PathBuf]) -> Vec<PathBuf> {
+    match event {
+        Ok(event) if !event.kind.is_access() => event
+            .paths
+            .into_iter()
+            .filter(|p| files.contains(p))
+            .collect(),
+        Ok(_) => vec![],
+        Err(err) => {
+            debug!("Watch error: {err}");
+            vec![]
+        }
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    let path = std::path::absolute(path)?;
+    let parent = path
+        .parent()
+        .with_context(|| format!("{} has no parent directory", path.display()))?;
+    let name = path
+        .file_name()
+        .with_context(|| format!("{} is not a file", path.display()))?;
+    Ok(parent.canonicalize()?.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::EventKind;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    fn event(kind: EventKind, paths: &[&str]) -> notify::Result<Event> {
+        let mut event = Event::new(kind);
+        event.paths = paths.iter().map(PathBuf::from).collect();
+        Ok(event)
+    }
+
+    #[test]
+    fn diff_shows_changed_lines() {
+        let diff = diff(
+            "Average Damage: 1.000\nStd Dev: 0.500\n",
+            "Average Damage: 1.500\nStd Dev: 0.500\n",
+        );
+        assert_eq!(
+            diff,
+            "Changes from previous output:\n- Average Damage: 1.000\n+ Average Damage: 1.500\n"
+        );
+    }
+
+    #[test]
+    fn diff_without_changes() {
+        assert_eq!(
+            diff("a\nb\n", "a\nb\n"),
+            "(no change from previous output)\n"
+        );
+    }
+
+    #[test]
+    fn watches_each_directory_once() {
+        let files = ["/a/weapon.toml", "/b/target.toml", "/a/other.toml"].map(PathBuf::from);
+        assert_eq!(watched_dirs(&files), [Path::new("/a"), Path::new("/b")]);
+    }
+
+    #[test]
+    fn only_changes_to_input_files_are_relevant() {
+        let files = ["/a/weapon.toml", "/b/target.toml"].map(PathBuf::from);
+        let modify = EventKind::Modify(ModifyKind::Any);
+        assert_eq!(
+            relevant_paths(event(modify, &["/a/weapon.toml", "/a/notes.txt"]), &files),
+            [PathBuf::from("/a/weapon.toml")]
+        );
+        // Editors which save by replacing the file create it again
+        let create = EventKind::Create(CreateKind::File);
+        assert_eq!(
+            relevant_paths(event(create, &["/b/target.toml"]), &files).len(),
+            1
+        );
+        // Reading a file doesn't change it
+        let access = EventKind::Access(AccessKind::Any);
+        assert!(relevant_paths(event(access, &["/a/weapon.toml"]), &files).is_empty());
+        let error = Err(notify::Error::generic("oops"));
+        assert!(relevant_paths(error, &files).is_empty());
+    }
+
+    #[test]
+    fn absolute_paths_for_files_which_do_not_exist_yet() {
+        let dir = std::env::temp_dir().canonicalize().unwrap();
+        let path = absolute(&std::env::temp_dir().join("missing-weapon.toml")).unwrap();
+        assert_eq!(path, dir.join("missing-weapon.toml"));
+    }
+}
@@ -1,16 +1,16 @@
-use crate::rerolls::RerollType;
 use crate::serde_utils::*;
+use aos_statshammer_core::RerollType;
 use aos_statshammer_core::weapon;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub(crate) struct WeaponConfig {
     characteristics: Characteristics,
     #[serde(default)]
     abilities: Abilities,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub(crate) struct Characteristics {
     attack: u8,
     hit: u8,
@@ -19,17 +19,17 @@ pub(crate) struct Characteristics {
     damage: u8,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub(crate) struct Abilities {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     bonus: Vec<BonusAbility>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     reroll: Vec<RerollAbility>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     exploding: Vec<ExplodingAbility>,
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum ValueCharacteristic {
     Attacks,
@@ -39,30 +39,37 @@ pub(crate) enum ValueCharacteristic {
     Damage,
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum RollCharacteristic {
     Hit,
     Wound,
 }
 
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub(crate) struct BonusAbility {
     to: ValueCharacteristic,
     #[serde(default = "default_i16::<1>")]
     amount: i16,
+    /// The rule which gives the bonus (for explaining the calculation).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub(crate) struct RerollAbility {
     to: RollCharacteristic,
     #[serde(rename = "type")]
     reroll_type: RerollType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Copy, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub(crate) struct ExplodingAbility {
     to: RollCharacteristic,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
     #[serde(default = "default_u8::<6>")]
     on: u8,
     #[serde(default = "default_bool::<false>")]
@@ -70,8 +77,8 @@ pub(crate) struct ExplodingAbility {
     #[serde(default = "default_u8::<1>")]
     extra: u8,
 }
-impl From<ExplodingAbility> for weapon::ExplodingAbility {
-    fn from(value: ExplodingAbility) -> Self {
+impl From<&ExplodingAbility> for weapon::ExplodingAbility {
+    fn from(value: &ExplodingAbility) -> Self {
         Self {
             on: value.on,
             unmodified: value.unmodified,
@@ -92,13 +99,13 @@ impl TryInto<weapon::Weapon> for WeaponConfig {
             .hit(weapon::Hit {
                 value: self.characteristics.hit,
                 bonus: self.bonus_to(ValueCharacteristic::Hit),
-                reroll: self.reroll_to(RollCharacteristic::Hit).map(Into::into),
+                reroll: self.reroll_to(RollCharacteristic::Hit),
                 exploding: self.exploding_to(RollCharacteristic::Hit).map(Into::into),
             })
             .wound(weapon::Wound {
                 value: self.characteristics.wound,
                 bonus: self.bonus_to(ValueCharacteristic::Wound),
-                reroll: self.reroll_to(RollCharacteristic::Wound).map(Into::into),
+                reroll: self.reroll_to(RollCharacteristic::Wound),
                 exploding: self.exploding_to(RollCharacteristic::Wound).map(Into::into),
             })
             .rend(weapon::Rend {
@@ -114,6 +121,96 @@ impl TryInto<weapon::Weapon> for WeaponConfig {
 }
 
 impl WeaponConfig {
+    /// A config with the given characteristics and no abilities.
+    pub fn new(attack: u8, hit: u8, wound: u8, rend: u8, damage: u8) -> Self {
+        Self {
+            characteristics: Characteristics {
+                attack,
+                hit,
+                wound,
+                rend,
+                damage,
+            },
+            abilities: Default::default(),
+        }
+    }
+
+    pub fn set_characteristic(&mut self, to: ValueCharacteristic, value: u8) {
+        let characteristic = match to {
+            ValueCharacteristic::Attacks => &mut self.characteristics.attack,
+            ValueCharacteristic::Hit => &mut self.characteristics.hit,
+            ValueCharacteristic::Wound => &mut self.characteristics.wound,
+            ValueCharacteristic::Rend => &mut self.characteristics.rend,
+            ValueCharacteristic::Damage => &mut self.characteristics.damage,
+        };
+        *characteristic = value;
+    }
+
+    pub fn add_bonus(&mut self, to: ValueCharacteristic, amount: i16, name: Option<String>) {
+        self.abilities.bonus.push(BonusAbility { to, amount, name });
+    }
+
+    /// Replaces any existing rerolls for the given characteristic.
+    pub fn set_reroll(
+        &mut self,
+        to: RollCharacteristic,
+        reroll_type: RerollType,
+        name: Option<String>,
+    ) {
+        self.abilities.reroll.retain(|a| a.to != to);
+        self.abilities.reroll.push(RerollAbility {
+            to,
+            reroll_type,
+            name,
+        });
+    }
+
+    /// Replaces any existing exploding ability for the given characteristic.
+    pub fn set_exploding(
+        &mut self,
+        to: RollCharacteristic,
+        on: u8,
+        unmodified: bool,
+        extra: u8,
+        name: Option<String>,
+    ) {
+        self.abilities.exploding.retain(|a| a.to != to);
+        self.abilities.exploding.push(ExplodingAbility {
+            to,
+            name,
+            on,
+            unmodified,
+            extra,
+        });
+    }
+
+    /// Any values which are out of range (these are not errors when converting,
+    /// but are almost certainly a mistake).
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+        let c = &self.characteristics;
+        if c.attack == 0 {
+            problems.push("attack should be at least 1".to_string());
+        }
+        for (name, value) in [("hit", c.hit), ("wound", c.wound)] {
+            if !(1..=6).contains(&value) {
+                problems.push(format!("{name} should be between 1 and 6 (got {value})"));
+            }
+        }
+        if c.damage == 0 {
+            problems.push("damage should be at least 1".to_string());
+        }
+        for ab in &self.abilities.exploding {
+            if !(1..=6).contains(&ab.on) {
+                problems.push(format!(
+                    "exploding `on` should be between 1 and 6 (got {})",
+                    ab.on
+                ));
+            }
+        }
+        problems
+    }
+
     fn bonus_to(&self, to: ValueCharacteristic) -> i16 {
         self.abilities
             .bonus
@@ -123,20 +220,81 @@ impl WeaponConfig {
     }
 
     fn reroll_to(&self, to: RollCharacteristic) -> Option<RerollType> {
+        self.reroll_source(to).map(|a| a.reroll_type)
+    }
+
+    fn exploding_to(&self, to: RollCharacteristic) -> Option<&ExplodingAbility> {
+        self.abilities.exploding.iter().find(|a| a.to == to)
+    }
+
+    /// Each rule giving a bonus to the characteristic, with the amount (these are
+    /// added together, see `bonus_to`).
+    pub fn bonus_sources(&self, to: ValueCharacteristic) -> Vec<(String, i16)> {
         self.abilities
-            .reroll
+            .bonus
             .iter()
             .filter(|a| a.to == to)
-            .map(|a| a.reroll_type)
-            .max()
+            .map(|a| {
+                let name = a
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("bonus to {}", value_name(to)));
+                (name, a.amount)
+            })
+            .collect()
     }
 
-    fn exploding_to(&self, to: RollCharacteristic) -> Option<ExplodingAbility> {
+    /// The rule giving the best reroll for the characteristic (the one which is used).
+    fn reroll_source(&self, to: RollCharacteristic) -> Option<&RerollAbility> {
         self.abilities
-            .exploding
+            .reroll
             .iter()
-            .find(|a| a.to == to)
-            .copied()
+            .filter(|a| a.to == to)
+            .max_by_key(|a| a.reroll_type)
+    }
+
+    /// The name of the rule giving the reroll which is used for the characteristic.
+    pub fn reroll_name(&self, to: RollCharacteristic) -> Option<String> {
+        self.reroll_source(to).map(|a| {
+            a.name.clone().unwrap_or_else(|| {
+                format!("reroll {} to {}", reroll_name(a.reroll_type), roll_name(to))
+            })
+        })
+    }
+
+    /// The name of the rule giving the exploding ability for the characteristic.
+    pub fn exploding_name(&self, to: RollCharacteristic) -> Option<String> {
+        self.exploding_to(to).map(|a| {
+            a.name
+                .clone()
+                .unwrap_or_else(|| format!("exploding {}s to {}", a.on, roll_name(to)))
+        })
+    }
+}
+
+fn value_name(to: ValueCharacteristic) -> &'static str {
+    match to {
+        ValueCharacteristic::Attacks => "attacks",
+        ValueCharacteristic::Hit => "hit",
+        ValueCharacteristic::Wound => "wound",
+        ValueCharacteristic::Rend => "rend",
+        ValueCharacteristic::Damage => "damage",
+    }
+}
+
+pub(crate) fn roll_name(to: RollCharacteristic) -> &'static str {
+    match to {
+        RollCharacteristic::Hit => "hit",
+        RollCharacteristic::Wound => "wound",
+    }
+}
+
+/// The name of the reroll, as written in config files and overrides.
+pub(crate) fn reroll_name(reroll_type: RerollType) -> &'static str {
+    match reroll_type {
+        RerollType::Ones => "ones",
+        RerollType::Failed => "failed",
+        RerollType::Any => "any",
     }
 }
 
@@ -163,10 +321,12 @@ mod tests {
                     BonusAbility {
                         to: ValueCharacteristic::Attacks,
                         amount: 1,
+                        name: None,
                     },
                     BonusAbility {
                         to: ValueCharacteristic::Attacks,
                         amount: 2,
+                        name: None,
                     },
                 ],
                 ..Default::default()
@@ -184,22 +344,27 @@ mod tests {
                     RerollAbility {
                         to: RollCharacteristic::Hit,
                         reroll_type: RerollType::Failed,
+                        name: None,
                     },
                     RerollAbility {
                         to: RollCharacteristic::Hit,
                         reroll_type: RerollType::Ones,
+                        name: None,
                     },
                     RerollAbility {
                         to: RollCharacteristic::Wound,
                         reroll_type: RerollType::Ones,
+                        name: None,
                     },
                     RerollAbility {
                         to: RollCharacteristic::Wound,
                         reroll_type: RerollType::Any,
+                        name: None,
                     },
                     RerollAbility {
                         to: RollCharacteristic::Wound,
                         reroll_type: RerollType::Failed,
+                        name: None,
                     },
                 ],
                 ..Default::default()
@@ -215,6 +380,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn problems_with_out_of_range_values() {
+        let mut config = WeaponConfig {
+            characteristics: basic_characteristics(),
+            abilities: Default::default(),
+        };
+        assert!(config.problems().is_empty());
+        config.characteristics.hit = 7;
+        config.characteristics.damage = 0;
+        assert_eq!(config.problems().len(), 2);
+    }
+
     #[test]
     fn create_weapon_basic() {
         let config = WeaponConfig {
@@ -0,0 +1,224 @@
+use crate::combat::{Combatant, Damage, Side, attacker_first, exchange};
+use crate::target::{Save, Target};
+use crate::unit::Unit;
+
+/// The expected state of both units at the end of a battle round.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Round {
+    pub round: u32,
+    pub attacker_models: f64,
+    pub target_models: f64,
+    /// The probability the target has been destroyed by the end of this round.
+    pub target_destroyed: f64,
+    /// The probability the attacker has been destroyed by the end of this round.
+    pub attacker_destroyed: f64,
+}
+
+/// The result of a unit attacking a target over several battle rounds.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attrition {
+    pub rounds: Vec<Round>,
+    /// `destroyed_in[r]` is the probability that the target is destroyed in round `r + 1`.
+    pub destroyed_in: Vec<f64>,
+    /// The probability that the target survives every round.
+    pub survives: f64,
+}
+
+/// The state after `rounds` battle rounds of `attacker` attacking `target`, which
+/// never attacks back. Losses reduce the attacker's attacks (see
+/// [`Unit::with_models`]), and damage carries over from one round to the next.
+pub fn attrition(attacker: &Unit, target: &Target, rounds: u32) -> Attrition {
+    // The attacker is never attacked, so its own defensive profile doesn't matter
+    let defence = Target {
+        models: attacker.models,
+        ..Target::from(Save::NONE)
+    };
+    let attacking = Side::new(Some(attacker), defence);
+    fight_rounds(attacking, Side::new(None, target.clone()), true, rounds)
+}
+
+/// The same as [`attrition`], except that the defender attacks back every battle
+/// round. The units fight in the order given by their [`Strike`]s, as in a single
+/// [`combat`].
+///
+/// [`Strike`]: crate::combat::Strike
+/// [`combat`]: crate::combat::combat
+pub fn fight(attacker: Combatant, defender: Combatant, rounds: u32) -> Attrition {
+    let attacker_first = attacker_first(attacker.strike, defender.strike);
+    fight_rounds(
+        Side::fighting(attacker),
+        Side::fighting(defender),
+        attacker_first,
+        rounds,
+    )
+}
+
+fn fight_rounds(
+    mut attacking: Side,
+    mut defending: Side,
+    attacker_first: bool,
+    rounds: u32,
+) -> Attrition {
+    let target_lethal = defending.lethal();
+    let mut damage = Damage::none();
+    let mut result = Attrition {
+        rounds: vec![],
+        destroyed_in: vec![],
+        survives: 1.0,
+    };
+    let mut destroyed = 0.0;
+    for round in 1..=rounds {
+        damage = exchange(&mut attacking, &mut defending, attacker_first, damage);
+
+        let expected = |f: &dyn Fn(usize, usize) -> f64| -> f64 {
+            damage.iter().map(|(&(a, t), p)| f(a, t) * p).sum()
+        };
+        let target_destroyed = expected(&|_, t| (t >= target_lethal) as u8 as f64);
+        result.destroyed_in.push(target_destroyed - destroyed);
+        destroyed = target_destroyed;
+        result.rounds.push(Round {
+            round,
+            attacker_models: expected(&|a, _| attacking.alive(a) as f64),
+            target_models: expected(&|_, t| defending.alive(t) as f64),
+            target_destroyed,
+            attacker_destroyed: expected(&|a, _| (attacking.alive(a) == 0) as u8 as f64),
+        });
+    }
+    result.survives = 1.0 - destroyed;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::Strike;
+    use crate::target::TargetBuilder;
+    use crate::unit::UnitWeapon;
+    use crate::weapon::WeaponBuilder;
+    use approx::assert_relative_eq;
+
+    fn unit(models: u32) -> Unit {
+        let weapon = WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(3)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap();
+        Unit {
+            models,
+            weapons: vec![UnitWeapon { weapon, models }],
+        }
+    }
+
+    fn target(models: u32) -> Target {
+        TargetBuilder::default()
+            .save(4)
+            .health(2)
+            .models(models)
+            .build()
+            .unwrap()
+    }
+
+    fn combatant<'a>(unit: &'a Unit, target: &'a Target, strike: Strike) -> Combatant<'a> {
+        Combatant {
+            unit,
+            target,
+            strike,
+        }
+    }
+
+    #[test]
+    fn first_round_matches_a_single_attack() {
+        let (unit, target) = (unit(5), target(5));
+        let result = attrition(&unit, &target, 1);
+        let slain = unit.models_slain(&target);
+        assert_relative_eq!(
+            result.rounds[0].target_models,
+            5.0 - slain.mean(),
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            result.rounds[0].target_destroyed,
+            unit.destroy_probability(&target),
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(result.rounds[0].attacker_models, 5.0, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn destroyed_in_adds_up() {
+        let result = attrition(&unit(5), &target(3), 4);
+        let total: f64 = result.destroyed_in.iter().sum::<f64>() + result.survives;
+        assert_relative_eq!(total, 1.0, max_relative = 1e-9);
+        assert!(
+            result
+                .rounds
+                .windows(2)
+                .all(|w| w[0].target_destroyed <= w[1].target_destroyed)
+        );
+    }
+
+    #[test]
+    fn retaliation_reduces_damage() {
+        let (attacker, defender) = (unit(5), unit(10));
+        let one_sided = attrition(&attacker, &target(10), 3);
+        let two_sided = fight(
+            combatant(&attacker, &target(5), Strike::Normal),
+            combatant(&defender, &target(10), Strike::Normal),
+            3,
+        );
+        assert!(two_sided.rounds[2].attacker_models < 5.0);
+        assert!(two_sided.rounds[2].target_models > one_sided.rounds[2].target_models);
+        // The first attack happens before any retaliation
+        assert_relative_eq!(
+            two_sided.rounds[0].target_models,
+            one_sided.rounds[0].target_models,
+            max_relative = 1e-9
+        );
+    }
+
+    #[test]
+    fn target_models_come_from_the_defending_unit() {
+        let (attacker, defender) = (unit(5), unit(10));
+        let result = fight(
+            combatant(&attacker, &target(5), Strike::Normal),
+            combatant(&defender, &target(3), Strike::Normal),
+            1,
+        );
+        let slain = attacker.models_slain(&target(10));
+        assert_relative_eq!(
+            result.rounds[0].target_models,
+            10.0 - slain.mean(),
+            max_relative = 1e-9
+        );
+    }
+
+    #[test]
+    fn first_round_matches_a_single_combat() {
+        let (attacker, defender) = (unit(5), unit(10));
+        let (attacker_target, defender_target) = (target(5), target(10));
+        let attacker = combatant(&attacker, &attacker_target, Strike::Last);
+        let defender = combatant(&defender, &defender_target, Strike::Normal);
+        let result = fight(attacker, defender, 1);
+        let combat = crate::combat::combat(attacker, defender);
+        assert_relative_eq!(
+            result.rounds[0].attacker_models,
+            5.0 - combat.attacker_losses.mean(),
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            result.rounds[0].target_models,
+            10.0 - combat.defender_losses.mean(),
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(
+            result.rounds[0].target_destroyed,
+            combat.defender_destroyed,
+            max_relative = 1e-9
+        );
+    }
+}
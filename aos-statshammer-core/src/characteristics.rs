@@ -1,4 +1,5 @@
 use crate::dice::D6;
+use crate::probability::Probability;
 
 // While these `macro_rules!` helpers could be turned into a proc-macro,
 // going to leave them as is for the time being.
@@ -7,6 +8,9 @@ pub trait Characteristic {
     fn bonus(&self) -> i16;
     fn unmodified(&self) -> u8;
     fn modified(&self) -> u8;
+    /// The modified value, or `None` if the bonus takes it out of the range of a `u8`
+    /// (rather than it being clamped, as it is by [`Characteristic::modified`]).
+    fn checked_modified(&self) -> Option<u8>;
 }
 
 macro_rules! impl_characteristic {
@@ -24,7 +28,11 @@ macro_rules! impl_characteristic {
             }
 
             fn modified(&self) -> u8 {
-                 (self.unmodified() as i16 $op self.bonus()).max(0) as u8
+                (self.unmodified() as i32 $op self.bonus() as i32).clamp(0, u8::MAX as i32) as u8
+            }
+
+            fn checked_modified(&self) -> Option<u8> {
+                u8::try_from(self.unmodified() as i32 $op self.bonus() as i32).ok()
             }
         }
 
@@ -57,7 +65,11 @@ macro_rules! impl_characteristic {
 }
 pub(crate) use impl_characteristic;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+/// The rolls which can be rerolled, ordered from the fewest to the most.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
 pub enum RerollType {
     Ones,
     Failed,
@@ -65,14 +77,31 @@ pub enum RerollType {
 }
 pub trait Reroll: Characteristic {
     fn reroll_type(&self) -> Option<RerollType>;
+    /// The probability that a single roll succeeds (before any rerolls).
+    fn success_probability(&self) -> f64 {
+        D6.probability(self.modified())
+    }
     fn reroll_probability(&self) -> f64 {
+        self.reroll_chance()
+    }
+    /// The probability that a single roll is rerolled, as any kind of [`Probability`].
+    fn reroll_chance<P: Probability>(&self) -> P {
         match self.reroll_type() {
-            None => 0.0,
-            Some(RerollType::Ones) => 1.0 / 6.0,
+            None => P::zero(),
+            Some(RerollType::Ones) => P::ratio(1, 6),
             Some(RerollType::Failed) => {
-                D6.inverse_probability(self.modified().min(self.unmodified()))
+                P::one() - D6.chance(self.modified().min(self.unmodified()))
             }
-            Some(RerollType::Any) => D6.inverse_probability(self.modified()),
+            Some(RerollType::Any) => P::one() - D6.chance(self.modified()),
+        }
+    }
+    /// Whether a roll of `face` would be rerolled.
+    fn rerolls(&self, face: u8) -> bool {
+        match self.reroll_type() {
+            None => false,
+            Some(RerollType::Ones) => face == 1,
+            Some(RerollType::Failed) => face < self.modified().min(self.unmodified()),
+            Some(RerollType::Any) => face < self.modified(),
         }
     }
 }
@@ -89,6 +118,9 @@ macro_rules! impl_reroll {
 pub(crate) use impl_reroll;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
 pub struct ExplodingAbility {
     pub on: u8,
     pub unmodified: bool,
@@ -104,21 +136,43 @@ impl Default for ExplodingAbility {
     }
 }
 pub trait Exploding: Characteristic {
-    fn extra_probability(&self) -> f64;
+    fn exploding_ability(&self) -> Option<ExplodingAbility>;
+    /// The roll needed to trigger the exploding ability (if there is one).
+    fn exploding_target(&self) -> Option<u8> {
+        self.exploding_ability().map(|ab| match ab.unmodified {
+            true => ab.on,
+            false => (ab.on as i16 - self.bonus()).max(0) as u8,
+        })
+    }
+    /// The probability that a single roll triggers the exploding ability.
+    fn exploding_probability(&self) -> f64 {
+        self.exploding_chance()
+    }
+    fn extra_probability(&self) -> f64 {
+        self.extra_chance()
+    }
+    /// [`Exploding::exploding_probability`], as any kind of [`Probability`].
+    fn exploding_chance<P: Probability>(&self) -> P {
+        self.exploding_target()
+            .map_or_else(P::zero, |target| D6.chance(target))
+    }
+    /// The average number of extra rolls from a single roll, as any kind of
+    /// [`Probability`].
+    fn extra_chance<P: Probability>(&self) -> P {
+        self.exploding_ability().map_or_else(P::zero, |ab| {
+            P::ratio(ab.extra as u64, 1) * self.exploding_chance()
+        })
+    }
+    /// Whether a roll of `face` would trigger the exploding ability.
+    fn explodes(&self, face: u8) -> bool {
+        self.exploding_target().is_some_and(|target| face >= target)
+    }
 }
 macro_rules! impl_exploding {
     ($bound: ty, $prop: ident) => {
         impl Exploding for $bound {
-            fn extra_probability(&self) -> f64 {
-                if let Some(ab) = self.$prop {
-                    let roll_target = match ab.unmodified {
-                        true => ab.on,
-                        false => (ab.on as i16 - self.bonus()).max(0) as u8,
-                    };
-                    ab.extra as f64 * D6.probability(roll_target)
-                } else {
-                    0.0
-                }
+            fn exploding_ability(&self) -> Option<ExplodingAbility> {
+                self.$prop
             }
         }
     };
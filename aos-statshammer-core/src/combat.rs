@@ -0,0 +1,287 @@
+use crate::distribution::Distribution;
+use crate::target::Target;
+use crate::unit::Unit;
+use std::collections::{BTreeMap, HashMap};
+
+/// When a unit fights in the combat phase.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Strike {
+    First,
+    #[default]
+    Normal,
+    Last,
+}
+
+/// One side of a combat: how it attacks, and how it defends.
+#[derive(Debug, Clone, Copy)]
+pub struct Combatant<'a> {
+    pub unit: &'a Unit,
+    /// The unit's defensive profile. The number of models comes from the `unit`.
+    pub target: &'a Target,
+    pub strike: Strike,
+}
+
+impl Combatant<'_> {
+    /// The defensive profile, with the number of models in the unit.
+    fn defence(&self) -> Target {
+        Target {
+            models: self.unit.models,
+            ..self.target.clone()
+        }
+    }
+}
+
+/// The result of two units fighting each other once.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Combat {
+    /// Whether the attacker fights before the defender.
+    pub attacker_first: bool,
+    /// The number of the attacker's models slain.
+    pub attacker_losses: Distribution,
+    /// The number of the defender's models slain.
+    pub defender_losses: Distribution,
+    pub attacker_destroyed: f64,
+    pub defender_destroyed: f64,
+}
+
+/// Whether the attacker (the active player's unit) fights first: a unit with
+/// strike-first fights before one without, and one with strike-last fights after
+/// one without. Otherwise the attacker fights first.
+pub fn attacker_first(attacker: Strike, defender: Strike) -> bool {
+    attacker <= defender
+}
+
+/// `attacker` and `defender` fight each other, in the order given by their
+/// [`Strike`]s. The unit which fights second only attacks with the models which
+/// survived (see [`Unit::with_models`]).
+pub fn combat(attacker: Combatant, defender: Combatant) -> Combat {
+    let attacker_first = attacker_first(attacker.strike, defender.strike);
+    let (mut attacking, mut defending) = (Side::fighting(attacker), Side::fighting(defender));
+    let damage = exchange(
+        &mut attacking,
+        &mut defending,
+        attacker_first,
+        Damage::none(),
+    );
+
+    let losses = |side: &Side, damage_to: fn(&(usize, usize)) -> usize| {
+        Distribution::mixture(damage.iter().map(|(d, &p)| {
+            let slain = side.models() - side.alive(damage_to(d));
+            (p, Distribution::certain(slain as usize))
+        }))
+    };
+    let attacker_losses = losses(&attacking, |&(a, _)| a);
+    let defender_losses = losses(&defending, |&(_, d)| d);
+    let destroyed = |losses: &Distribution, models: u32| losses.at_least(models.max(1) as usize);
+    Combat {
+        attacker_first,
+        attacker_destroyed: destroyed(&attacker_losses, attacker.unit.models),
+        defender_destroyed: destroyed(&defender_losses, defender.unit.models),
+        attacker_losses,
+        defender_losses,
+    }
+}
+
+/// One side of a fight, which carries the damage it has taken from one exchange of
+/// attacks to the next. A side without a unit only defends.
+pub(crate) struct Side<'a> {
+    unit: Option<&'a Unit>,
+    /// The defensive profile, with the number of models in the unit.
+    defence: Target,
+    /// The damage distribution of the unit, for each number of models still alive.
+    damage: HashMap<u32, Distribution>,
+}
+
+impl<'a> Side<'a> {
+    pub(crate) fn new(unit: Option<&'a Unit>, defence: Target) -> Self {
+        Self {
+            unit,
+            defence,
+            damage: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn fighting(combatant: Combatant<'a>) -> Self {
+        Self::new(Some(combatant.unit), combatant.defence())
+    }
+
+    pub(crate) fn models(&self) -> u32 {
+        self.defence.models
+    }
+
+    /// The damage which destroys the unit.
+    pub(crate) fn lethal(&self) -> usize {
+        self.models() as usize * self.defence.health.max(1) as usize
+    }
+
+    /// The number of models still alive after taking `damage`.
+    pub(crate) fn alive(&self, damage: usize) -> u32 {
+        let slain = (damage / self.defence.health.max(1) as usize).min(self.models() as usize);
+        self.models() - slain as u32
+    }
+
+    /// The damage dealt to `other` by this side's surviving models (if it can attack).
+    fn attack(&mut self, alive: u32, other: &Target) -> Option<&Distribution> {
+        let unit = self.unit.filter(|_| alive > 0)?;
+        Some(
+            self.damage
+                .entry(alive)
+                .or_insert_with(|| unit.with_models(alive).damage_distribution(other)),
+        )
+    }
+}
+
+/// The probability of each `(damage to the attacker, damage to the defender)`.
+pub(crate) struct Damage(BTreeMap<(usize, usize), f64>);
+
+impl Damage {
+    /// Before either side has taken any damage.
+    pub(crate) fn none() -> Self {
+        Self(BTreeMap::from([((0, 0), 1.0)]))
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&(usize, usize), &f64)> {
+        self.0.iter()
+    }
+
+    fn swapped(self) -> Self {
+        Self(self.0.into_iter().map(|((a, b), p)| ((b, a), p)).collect())
+    }
+}
+
+/// One exchange of attacks: the side which fights first attacks, then the survivors
+/// of the other side attack back.
+pub(crate) fn exchange(
+    attacker: &mut Side,
+    defender: &mut Side,
+    attacker_first: bool,
+    damage: Damage,
+) -> Damage {
+    if attacker_first {
+        strike(attacker, defender, damage)
+    } else {
+        strike(defender, attacker, damage.swapped()).swapped()
+    }
+}
+
+/// `first` attacks `second`, then `second` attacks back. The damage is keyed by
+/// `(damage to first, damage to second)`.
+fn strike(first: &mut Side, second: &mut Side, damage: Damage) -> Damage {
+    let (first_lethal, second_lethal) = (first.lethal(), second.lethal());
+    let first_defence = first.defence.clone();
+    let mut next = BTreeMap::new();
+    for ((first_damage, second_damage), p) in damage.0 {
+        let alive = first.alive(first_damage);
+        let dealt = match first.attack(alive, &second.defence) {
+            Some(dealt) if second_damage < second_lethal => dealt,
+            _ => {
+                *next.entry((first_damage, second_damage)).or_insert(0.0) += p;
+                continue;
+            }
+        };
+        for (dealt, q) in dealt.pmf().iter().enumerate().filter(|(_, q)| **q > 0.0) {
+            let second_damage = (second_damage + dealt).min(second_lethal);
+            let taken = match second.attack(second.alive(second_damage), &first_defence) {
+                Some(taken) => taken,
+                None => {
+                    *next.entry((first_damage, second_damage)).or_insert(0.0) += p * q;
+                    continue;
+                }
+            };
+            for (taken, r) in taken.pmf().iter().enumerate().filter(|(_, r)| **r > 0.0) {
+                let first_damage = (first_damage + taken).min(first_lethal);
+                *next.entry((first_damage, second_damage)).or_insert(0.0) += p * q * r;
+            }
+        }
+    }
+    Damage(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target::TargetBuilder;
+    use crate::unit::UnitWeapon;
+    use crate::weapon::WeaponBuilder;
+    use approx::assert_relative_eq;
+
+    fn unit(models: u32) -> Unit {
+        let weapon = WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(3)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap();
+        Unit {
+            models,
+            weapons: vec![UnitWeapon { weapon, models }],
+        }
+    }
+
+    fn target() -> Target {
+        TargetBuilder::default().save(4).health(2).build().unwrap()
+    }
+
+    fn combatant<'a>(unit: &'a Unit, target: &'a Target, strike: Strike) -> Combatant<'a> {
+        Combatant {
+            unit,
+            target,
+            strike,
+        }
+    }
+
+    #[test]
+    fn strike_order() {
+        use Strike::*;
+        assert!(attacker_first(Normal, Normal));
+        assert!(attacker_first(First, First));
+        assert!(!attacker_first(Normal, First));
+        assert!(!attacker_first(Last, Normal));
+        assert!(attacker_first(Normal, Last));
+    }
+
+    #[test]
+    fn first_strike_is_unaffected_by_retaliation() {
+        let (attacker, defender, target) = (unit(5), unit(10), target());
+        let result = combat(
+            combatant(&attacker, &target, Strike::Normal),
+            combatant(&defender, &target, Strike::Normal),
+        );
+        assert!(result.attacker_first);
+        let defence = Target {
+            models: 10,
+            ..target.clone()
+        };
+        let expected = attacker.models_slain(&defence);
+        assert_eq!(result.defender_losses.max(), expected.max());
+        for (p, expected) in result.defender_losses.pmf().iter().zip(expected.pmf()) {
+            assert_relative_eq!(p, expected, max_relative = 1e-9);
+        }
+        assert_relative_eq!(
+            result.attacker_losses.pmf().iter().sum::<f64>(),
+            1.0,
+            max_relative = 1e-9
+        );
+    }
+
+    #[test]
+    fn striking_first_reduces_losses() {
+        let (attacker, defender, target) = (unit(5), unit(10), target());
+        let first = combat(
+            combatant(&attacker, &target, Strike::First),
+            combatant(&defender, &target, Strike::Normal),
+        );
+        let last = combat(
+            combatant(&attacker, &target, Strike::Last),
+            combatant(&defender, &target, Strike::Normal),
+        );
+        assert!(!last.attacker_first);
+        assert!(first.attacker_losses.mean() < last.attacker_losses.mean());
+        assert!(first.defender_losses.mean() > last.defender_losses.mean());
+    }
+}
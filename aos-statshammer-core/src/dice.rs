@@ -1,3 +1,5 @@
+use crate::probability::Probability;
+
 pub(crate) struct Dice {
     sides: usize,
 }
@@ -9,17 +11,13 @@ impl Dice {
     }
 
     pub fn probability(&self, target: u8) -> f64 {
-        let upper_bounds = (self.sides as f64) + 1.0;
-        let target: f64 = target.into();
-        if target > upper_bounds {
-            0.0
-        } else {
-            ((upper_bounds - target) / self.sides as f64).clamp(0.0, 1.0)
-        }
+        self.chance(target)
     }
 
-    pub fn inverse_probability(&self, target: u8) -> f64 {
-        1.0 - self.probability(target)
+    /// The probability of rolling `target` or more, as any kind of [`Probability`].
+    pub fn chance<P: Probability>(&self, target: u8) -> P {
+        let successes = (self.sides + 1).saturating_sub(target.max(1) as usize);
+        P::ratio(successes.min(self.sides) as u64, self.sides as u64)
     }
 }
 
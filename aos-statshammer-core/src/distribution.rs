@@ -0,0 +1,321 @@
+use crate::probability::Probability;
+
+/// A discrete probability distribution over the non-negative integers, stored
+/// as its probability mass function (`pmf[k]` is `P(X = k)`). The probabilities
+/// are `f64`s, unless they are calculated exactly (see [`Probability`]).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Distribution<P = f64> {
+    pmf: Vec<P>,
+}
+
+/// The statistics which describe a distribution, as well as its mean: how much it
+/// varies and how likely it is to be zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
+pub struct Summary {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub coefficient_of_variation: Option<f64>,
+    /// The 10th percentile (9 times in 10 the value is at least this).
+    pub p10: usize,
+    /// The median.
+    pub p50: usize,
+    /// The 90th percentile (only 1 time in 10 the value is more than this).
+    pub p90: usize,
+    /// `P(X = 0)`
+    pub zero: f64,
+}
+
+impl<P: Probability> Distribution<P> {
+    /// A distribution which is always `value`.
+    pub fn certain(value: usize) -> Self {
+        let mut pmf = vec![P::zero(); value + 1];
+        pmf[value] = P::one();
+        Self { pmf }
+    }
+
+    /// A single trial which is `1` with the given probability (otherwise `0`).
+    pub(crate) fn bernoulli(probability: P) -> Self {
+        let probability = if probability > P::one() {
+            P::one()
+        } else if probability < P::zero() {
+            P::zero()
+        } else {
+            probability
+        };
+        Self {
+            pmf: vec![P::one() - probability.clone(), probability],
+        }
+    }
+
+    /// The number of successes from `trials` independent trials.
+    pub(crate) fn binomial(trials: usize, probability: P) -> Self {
+        Self::bernoulli(probability).repeat(trials)
+    }
+
+    /// A mixture of the given distributions, each chosen with the given probability.
+    pub(crate) fn mixture(components: impl IntoIterator<Item = (P, Self)>) -> Self {
+        components
+            .into_iter()
+            .fold(Self::empty(), |result, (probability, dist)| {
+                result.mix(&dist, probability)
+            })
+            .trimmed()
+    }
+
+    /// The probability mass function, indexed by value.
+    pub fn pmf(&self) -> &[P] {
+        &self.pmf
+    }
+
+    /// The largest value with a non-zero probability.
+    pub fn max(&self) -> usize {
+        self.pmf.iter().rposition(|p| !p.is_zero()).unwrap_or(0)
+    }
+
+    /// `P(X = value)`
+    pub fn probability(&self, value: usize) -> P {
+        self.pmf.get(value).cloned().unwrap_or_else(P::zero)
+    }
+
+    /// `P(X >= value)`
+    pub fn at_least(&self, value: usize) -> P {
+        let total = self
+            .pmf
+            .iter()
+            .skip(value)
+            .fold(P::zero(), |total, p| total + p.clone());
+        if total > P::one() { P::one() } else { total }
+    }
+
+    pub fn mean(&self) -> P {
+        self.pmf.iter().enumerate().fold(P::zero(), |mean, (k, p)| {
+            mean + P::ratio(k as u64, 1) * p.clone()
+        })
+    }
+
+    /// The same distribution, with every probability as an `f64`.
+    pub fn to_f64(&self) -> Distribution {
+        Distribution {
+            pmf: self.pmf.iter().map(P::to_f64).collect(),
+        }
+    }
+
+    /// The distribution of the sum of `self` and `other` (when independent).
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        let mut pmf = vec![P::zero(); self.pmf.len() + other.pmf.len() - 1];
+        for (i, a) in self.pmf.iter().enumerate().filter(|(_, a)| !a.is_zero()) {
+            for (j, b) in other.pmf.iter().enumerate() {
+                pmf[i + j] += a.clone() * b.clone();
+            }
+        }
+        Self { pmf }.trimmed()
+    }
+
+    /// The distribution of the sum of `count` independent copies of `self`.
+    pub(crate) fn repeat(&self, count: usize) -> Self {
+        let mut result = Self::certain(0);
+        let mut base = self.clone();
+        let mut count = count;
+        while count > 0 {
+            if count & 1 == 1 {
+                result = result.add(&base);
+            }
+            count >>= 1;
+            if count > 0 {
+                base = base.add(&base);
+            }
+        }
+        result
+    }
+
+    /// The distribution of the sum of `N` independent copies of `item`, where
+    /// `N` is distributed according to `self`.
+    pub(crate) fn compound(&self, item: &Self) -> Self {
+        let mut result = Self::empty();
+        let mut sum = Self::certain(0);
+        for (n, p) in self.pmf.iter().enumerate() {
+            if n > 0 {
+                sum = sum.add(item);
+            }
+            if !p.is_zero() {
+                result = result.mix(&sum, p.clone());
+            }
+        }
+        result.trimmed()
+    }
+
+    /// The distribution of `f(X)`.
+    pub(crate) fn map(&self, f: impl Fn(usize) -> usize) -> Self {
+        let mut pmf = vec![P::zero(); 1];
+        for (k, p) in self.pmf.iter().enumerate().filter(|(_, p)| !p.is_zero()) {
+            let value = f(k);
+            if pmf.len() <= value {
+                pmf.resize(value + 1, P::zero());
+            }
+            pmf[value] += p.clone();
+        }
+        Self { pmf }
+    }
+
+    /// No probability of anything (which only makes sense as the start of a mixture).
+    fn empty() -> Self {
+        Self {
+            pmf: vec![P::zero()],
+        }
+    }
+
+    fn mix(&self, other: &Self, weight: P) -> Self {
+        let mut pmf = self.pmf.clone();
+        if pmf.len() < other.pmf.len() {
+            pmf.resize(other.pmf.len(), P::zero());
+        }
+        for (k, p) in other.pmf.iter().enumerate() {
+            pmf[k] += p.clone() * weight.clone();
+        }
+        Self { pmf }
+    }
+
+    /// Drop trailing zero probabilities (always keeping at least one value).
+    fn trimmed(mut self) -> Self {
+        let len = self.max() + 1;
+        self.pmf.truncate(len);
+        self
+    }
+}
+
+impl Distribution {
+    /// The distribution of outcomes observed in a sample, where `counts[k]` is the
+    /// number of times `k` was seen.
+    pub(crate) fn from_counts(counts: &[u64]) -> Self {
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return Self::certain(0);
+        }
+        Self {
+            pmf: counts.iter().map(|&c| c as f64 / total as f64).collect(),
+        }
+        .trimmed()
+    }
+
+    pub fn variance(&self) -> f64 {
+        let mean = self.mean();
+        self.pmf
+            .iter()
+            .enumerate()
+            .map(|(k, p)| (k as f64 - mean).powi(2) * p)
+            .sum()
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// The standard deviation relative to the mean (`None` if the mean is zero).
+    /// The lower it is, the more reliable the result.
+    pub fn coefficient_of_variation(&self) -> Option<f64> {
+        let mean = self.mean();
+        (mean > 0.0).then(|| self.std_dev() / mean)
+    }
+
+    /// The usual statistics about the distribution, together.
+    pub fn summary(&self) -> Summary {
+        Summary {
+            mean: self.mean(),
+            std_dev: self.std_dev(),
+            coefficient_of_variation: self.coefficient_of_variation(),
+            p10: self.percentile(0.1),
+            p50: self.median(),
+            p90: self.percentile(0.9),
+            zero: self.probability(0),
+        }
+    }
+
+    /// The smallest value where `P(X <= value) >= 0.5`.
+    pub fn median(&self) -> usize {
+        self.percentile(0.5)
+    }
+
+    /// The smallest value where `P(X <= value) >= fraction`.
+    pub fn percentile(&self, fraction: f64) -> usize {
+        let mut cumulative = 0.0;
+        for (k, p) in self.pmf.iter().enumerate() {
+            cumulative += p;
+            // Allow for a bit of floating point error in the cumulative sum
+            if cumulative >= fraction - 1e-9 {
+                return k;
+            }
+        }
+        self.max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn assert_total(dist: &Distribution) {
+        assert_relative_eq!(dist.pmf().iter().sum::<f64>(), 1.0, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn binomial() {
+        let dist = Distribution::binomial(2, 0.5);
+        assert_total(&dist);
+        assert_eq!(dist.pmf(), &[0.25, 0.5, 0.25]);
+        assert_relative_eq!(dist.mean(), 1.0);
+        assert_eq!(dist.median(), 1);
+    }
+
+    #[test]
+    fn summary() {
+        let summary = Distribution::binomial(4, 0.5).summary();
+        assert_relative_eq!(summary.mean, 2.0, max_relative = 1e-9);
+        assert_relative_eq!(summary.std_dev, 1.0, max_relative = 1e-9);
+        assert_relative_eq!(
+            summary.coefficient_of_variation.unwrap(),
+            0.5,
+            max_relative = 1e-9
+        );
+        assert_eq!((summary.p10, summary.p50, summary.p90), (1, 2, 3));
+        assert_relative_eq!(summary.zero, 1.0 / 16.0, max_relative = 1e-9);
+        assert_eq!(Distribution::certain(0).coefficient_of_variation(), None);
+    }
+
+    #[test]
+    fn compound_mean_is_product_of_means() {
+        let count = Distribution::binomial(3, 0.5);
+        let item = Distribution::binomial(2, 0.25);
+        let dist = count.compound(&item);
+        assert_total(&dist);
+        assert_relative_eq!(dist.mean(), 1.5 * 0.5, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn mixture() {
+        let dist = Distribution::mixture([
+            (0.5, Distribution::certain(0)),
+            (0.5, Distribution::certain(2)),
+        ]);
+        assert_eq!(dist.pmf(), &[0.5, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn from_counts() {
+        let dist = Distribution::from_counts(&[1, 3, 0]);
+        assert_eq!(dist.pmf(), &[0.25, 0.75]);
+        assert_eq!(Distribution::from_counts(&[]), Distribution::certain(0));
+    }
+
+    #[test]
+    fn percentiles() {
+        let dist = Distribution::binomial(4, 0.5);
+        assert_eq!(dist.percentile(0.0), 0);
+        assert_eq!(dist.percentile(0.1), 1);
+        assert_eq!(dist.percentile(0.9), 3);
+        assert_eq!(dist.percentile(1.0), 4);
+    }
+}
@@ -1,6 +1,16 @@
+pub mod attrition;
 mod characteristics;
+pub mod combat;
 mod dice;
+pub mod distribution;
+pub mod probability;
+#[cfg(feature = "serde")]
+pub mod report;
+pub mod sensitivity;
+mod simulation;
 pub mod target;
+pub mod unit;
+pub mod validate;
 pub mod weapon;
 
-pub use characteristics::RerollType;
+pub use characteristics::{Characteristic, Exploding, Reroll, RerollType};
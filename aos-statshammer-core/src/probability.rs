@@ -0,0 +1,80 @@
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+/// The numbers that probabilities are calculated with: `f64` normally, or [`Exact`]
+/// fractions (with the `exact` feature).
+pub trait Probability:
+    Clone
+    + Debug
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + AddAssign
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// `numerator / denominator`
+    fn ratio(numerator: u64, denominator: u64) -> Self;
+    fn to_f64(&self) -> f64;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+impl Probability for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn ratio(numerator: u64, denominator: u64) -> Self {
+        numerator as f64 / denominator as f64
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+}
+
+/// An exact fraction, for calculating probabilities without any rounding errors
+/// (at the cost of speed).
+#[cfg(feature = "exact")]
+pub type Exact = num_rational::BigRational;
+
+#[cfg(feature = "exact")]
+impl Probability for Exact {
+    fn zero() -> Self {
+        num_traits::Zero::zero()
+    }
+
+    fn one() -> Self {
+        num_traits::One::one()
+    }
+
+    fn ratio(numerator: u64, denominator: u64) -> Self {
+        Exact::new(numerator.into(), denominator.into())
+    }
+
+    fn to_f64(&self) -> f64 {
+        num_traits::ToPrimitive::to_f64(self).unwrap_or(f64::NAN)
+    }
+}
+
+#[cfg(all(test, feature = "exact"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_ratios() {
+        let third = Exact::ratio(2, 6);
+        assert_eq!(third.clone() + third.clone() + third, Exact::one());
+        assert_eq!(Exact::ratio(1, 4).to_f64(), 0.25);
+        assert!(Exact::ratio(0, 6).is_zero());
+    }
+}
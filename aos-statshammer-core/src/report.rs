@@ -0,0 +1,92 @@
+//! Serializable results, shared by everything which sends them over the wire (the
+//! CLI's JSON output, the REST and JSON-RPC servers and the WebAssembly bindings).
+
+use crate::distribution::{Distribution, Summary};
+use crate::target::Target;
+use crate::weapon::Weapon;
+use serde::{Deserialize, Serialize};
+
+/// A damage distribution, with the statistics which describe it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
+pub struct DistributionReport {
+    pub summary: Summary,
+    /// `P(damage = k)`, indexed by `k`
+    pub probability: Vec<f64>,
+    /// `P(damage >= k)`, indexed by `k`
+    pub at_least: Vec<f64>,
+}
+
+impl From<&Distribution> for DistributionReport {
+    fn from(dist: &Distribution) -> Self {
+        Self {
+            summary: dist.summary(),
+            probability: dist.pmf().to_vec(),
+            at_least: (0..=dist.max()).map(|k| dist.at_least(k)).collect(),
+        }
+    }
+}
+
+/// The damage against a single save, in a table of every save.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
+pub struct SaveTableRow {
+    /// The save characteristic, e.g. "4+" (or "-" for no save)
+    pub save: String,
+    pub average_damage: f64,
+    /// `None` if the weapon can do too much damage to calculate the distribution
+    /// quickly (see [`Weapon::damage_summary`])
+    pub summary: Option<Summary>,
+}
+
+impl SaveTableRow {
+    /// A row for every save, from 2+ down to no save (see [`Weapon::save_table`]).
+    pub fn for_weapon(weapon: &Weapon, target: &Target) -> Vec<Self> {
+        weapon
+            .save_table(target)
+            .into_iter()
+            .map(|(save, average_damage)| Self {
+                save: save.to_string(),
+                average_damage,
+                summary: weapon.damage_summary(&target.with_save(save.value)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target::Save;
+    use crate::weapon::WeaponBuilder;
+
+    #[test]
+    fn save_table_rows() {
+        let weapon = WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap();
+        let rows = SaveTableRow::for_weapon(&weapon, &Target::from(Save::NONE));
+        assert_eq!(rows.len(), Save::ALL.len());
+        assert_eq!(rows[2].save, "4+");
+        assert_eq!(
+            rows[2].average_damage,
+            weapon.average_damage(&Target::from(4))
+        );
+        assert!((rows[2].summary.unwrap().mean - rows[2].average_damage).abs() < 1e-9);
+
+        let json = serde_json::to_value(DistributionReport::from(
+            &weapon.damage_distribution(&Target::from(4)),
+        ))
+        .unwrap();
+        assert_eq!(
+            json["at_least"].as_array().unwrap().len(),
+            json["probability"].as_array().unwrap().len()
+        );
+        assert!(json["summary"]["mean"].is_number());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::RerollType;
+use crate::characteristics::ExplodingAbility;
+use crate::target::Target;
+use crate::weapon::Weapon;
+
+/// A single improvement to a weapon, such as from an enhancement or a spell.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Improvement {
+    Attacks,
+    Hit,
+    Wound,
+    Rend,
+    Damage,
+    RerollHit(RerollType),
+    RerollWound(RerollType),
+    ExplodingHit,
+    ExplodingWound,
+}
+
+impl Improvement {
+    pub const ALL: [Improvement; 13] = [
+        Improvement::Attacks,
+        Improvement::Hit,
+        Improvement::Wound,
+        Improvement::Rend,
+        Improvement::Damage,
+        Improvement::RerollHit(RerollType::Ones),
+        Improvement::RerollHit(RerollType::Failed),
+        Improvement::RerollHit(RerollType::Any),
+        Improvement::RerollWound(RerollType::Ones),
+        Improvement::RerollWound(RerollType::Failed),
+        Improvement::RerollWound(RerollType::Any),
+        Improvement::ExplodingHit,
+        Improvement::ExplodingWound,
+    ];
+
+    /// A copy of `weapon` with this improvement. A reroll or exploding ability
+    /// never replaces a better one which the weapon already has.
+    pub fn apply(&self, weapon: &Weapon) -> Weapon {
+        let mut weapon = weapon.clone();
+        match *self {
+            Improvement::Attacks => weapon.attacks = weapon.attacks + 1,
+            Improvement::Hit => weapon.hit = weapon.hit + 1,
+            Improvement::Wound => weapon.wound = weapon.wound + 1,
+            Improvement::Rend => weapon.rend = weapon.rend + 1,
+            Improvement::Damage => weapon.damage = weapon.damage + 1,
+            Improvement::RerollHit(reroll) => {
+                weapon.hit.reroll = weapon.hit.reroll.max(Some(reroll));
+            }
+            Improvement::RerollWound(reroll) => {
+                weapon.wound.reroll = weapon.wound.reroll.max(Some(reroll));
+            }
+            Improvement::ExplodingHit => {
+                weapon.hit.exploding = weapon.hit.exploding.or(Some(ExplodingAbility::default()));
+            }
+            Improvement::ExplodingWound => {
+                weapon.wound.exploding =
+                    weapon.wound.exploding.or(Some(ExplodingAbility::default()));
+            }
+        }
+        weapon
+    }
+}
+
+impl std::fmt::Display for Improvement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reroll = |reroll| match reroll {
+            RerollType::Ones => "ones",
+            RerollType::Failed => "failed",
+            RerollType::Any => "any",
+        };
+        match self {
+            Improvement::Attacks => write!(f, "+1 attacks"),
+            Improvement::Hit => write!(f, "+1 to hit"),
+            Improvement::Wound => write!(f, "+1 to wound"),
+            Improvement::Rend => write!(f, "+1 rend"),
+            Improvement::Damage => write!(f, "+1 damage"),
+            Improvement::RerollHit(r) => write!(f, "reroll {} hit rolls", reroll(*r)),
+            Improvement::RerollWound(r) => write!(f, "reroll {} wound rolls", reroll(*r)),
+            Improvement::ExplodingHit => write!(f, "exploding 6s to hit"),
+            Improvement::ExplodingWound => write!(f, "exploding 6s to wound"),
+        }
+    }
+}
+
+/// The average damage with an improvement, and how much it adds.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gain {
+    pub improvement: Improvement,
+    pub average_damage: f64,
+    pub gain: f64,
+    /// The gain as a fraction of the damage without the improvement (if there is any).
+    pub relative_gain: Option<f64>,
+}
+
+/// The gain in average damage from each single [`Improvement`], from the most to the
+/// least (improvements which make no difference are kept, with a gain of 0).
+pub fn sensitivity(weapon: &Weapon, target: &Target) -> Vec<Gain> {
+    let base = weapon.average_damage(target);
+    let mut gains: Vec<Gain> = Improvement::ALL
+        .iter()
+        .map(|&improvement| {
+            let average_damage = improvement.apply(weapon).average_damage(target);
+            let gain = average_damage - base;
+            Gain {
+                improvement,
+                average_damage,
+                gain,
+                relative_gain: (base > 0.0).then(|| gain / base),
+            }
+        })
+        .collect();
+    // A stable sort, so ties keep the order of `Improvement::ALL`
+    gains.sort_by(|a, b| b.gain.total_cmp(&a.gain));
+    gains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weapon::WeaponBuilder;
+    use approx::assert_relative_eq;
+
+    fn weapon() -> Weapon {
+        WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn apply_uses_bonuses() {
+        let improved = Improvement::Hit.apply(&weapon());
+        assert_eq!((improved.hit.value, improved.hit.bonus), (3, 1));
+        let improved = Improvement::Rend.apply(&weapon());
+        assert_eq!(improved.rend.bonus, 1);
+    }
+
+    #[test]
+    fn apply_keeps_better_rerolls() {
+        let mut weapon = weapon();
+        weapon.hit.reroll = Some(RerollType::Any);
+        let improved = Improvement::RerollHit(RerollType::Ones).apply(&weapon);
+        assert_eq!(improved.hit.reroll, Some(RerollType::Any));
+    }
+
+    #[test]
+    fn ranked_by_gain() {
+        let gains = sensitivity(&weapon(), &Target::from(4));
+        assert_eq!(gains.len(), Improvement::ALL.len());
+        assert!(gains.windows(2).all(|w| w[0].gain >= w[1].gain));
+        // Doubling the damage doubles the average
+        let damage = gains
+            .iter()
+            .find(|g| g.improvement == Improvement::Damage)
+            .unwrap();
+        assert_eq!(gains[0].improvement, Improvement::Damage);
+        assert_relative_eq!(damage.relative_gain.unwrap(), 1.0, max_relative = 1e-9);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::characteristics::*;
+use crate::distribution::Distribution;
+use crate::target::Target;
+use crate::weapon::Weapon;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+impl Weapon {
+    /// Simulate `trials` sets of attacks against `target` by rolling dice, returning
+    /// the observed distribution of the damage dealt. The same `seed` will always
+    /// give the same result.
+    pub fn simulate_damage(&self, target: &Target, trials: usize, seed: u64) -> Distribution {
+        let mut dice = Dice::new(seed);
+        let mut counts: Vec<u64> = vec![];
+        for _ in 0..trials {
+            let damage = self.simulate_once(target, &mut dice);
+            if counts.len() <= damage {
+                counts.resize(damage + 1, 0);
+            }
+            counts[damage] += 1;
+        }
+        Distribution::from_counts(&counts)
+    }
+
+    fn simulate_once(&self, target: &Target, dice: &mut Dice) -> usize {
+        let hits: usize = (0..self.attacks.modified())
+            .map(|_| dice.successes(&self.hit))
+            .sum();
+        let wounds: usize = (0..hits).map(|_| dice.successes(&self.wound)).sum();
+        let save = target.save_roll(self.rend.modified());
+        let unsaved = (0..wounds).filter(|_| !dice.passes(&save)).count();
+        let damage = unsaved * self.damage.modified() as usize;
+        match target.ward {
+            Some(ward) => (0..damage).filter(|_| dice.d6() < ward).count(),
+            None => damage,
+        }
+    }
+}
+
+struct Dice {
+    rng: SmallRng,
+}
+
+impl Dice {
+    fn new(seed: u64) -> Self {
+        Self {
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    fn d6(&mut self) -> u8 {
+        self.rng.random_range(1..=6)
+    }
+
+    /// Roll against a characteristic which can be rerolled (but can't explode).
+    fn passes<C: Reroll>(&mut self, characteristic: &C) -> bool {
+        let target = characteristic.modified();
+        let face = self.d6();
+        face >= target || (characteristic.rerolls(face) && self.d6() >= target)
+    }
+
+    /// Roll for a single attack/hit, returning the number of successes (including
+    /// any rerolls and extra rolls from an exploding ability).
+    fn successes<C: Reroll + Exploding>(&mut self, characteristic: &C) -> usize {
+        let target = characteristic.modified();
+        let face = self.d6();
+        let mut successes = 0;
+        if let Some(ab) = characteristic.exploding_ability()
+            && characteristic.explodes(face)
+        {
+            successes += (0..ab.extra).filter(|_| self.d6() >= target).count();
+        }
+        if face >= target || (characteristic.rerolls(face) && self.d6() >= target) {
+            successes += 1;
+        }
+        successes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RerollType;
+    use crate::target::{Target, TargetBuilder};
+    use crate::weapon::{ExplodingAbility, WeaponBuilder};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn simulation_is_close_to_exact() {
+        let mut weapon = WeaponBuilder::default()
+            .attacks(4)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(2)
+            .build()
+            .unwrap();
+        weapon.hit.reroll = Some(RerollType::Ones);
+        weapon.wound.exploding = Some(ExplodingAbility::default());
+        let target = TargetBuilder::default()
+            .save(4)
+            .reroll(RerollType::Ones)
+            .ward(6)
+            .build()
+            .unwrap();
+
+        let exact = weapon.damage_distribution(&target);
+        let simulated = weapon.simulate_damage(&target, 100_000, 1);
+        assert_abs_diff_eq!(simulated.mean(), exact.mean(), epsilon = 0.05);
+        for k in 0..=exact.max() {
+            assert_abs_diff_eq!(
+                simulated.probability(k),
+                exact.probability(k),
+                epsilon = 0.01
+            );
+        }
+    }
+
+    #[test]
+    fn simulation_is_repeatable() {
+        let weapon = WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(4)
+            .rend(0)
+            .damage(1)
+            .build()
+            .unwrap();
+        let target = Target::from(4);
+        assert_eq!(
+            weapon.simulate_damage(&target, 1000, 7),
+            weapon.simulate_damage(&target, 1000, 7)
+        );
+    }
+}
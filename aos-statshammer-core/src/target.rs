@@ -1,37 +1,161 @@
 use crate::RerollType;
 use crate::characteristics::*;
 use crate::dice::D6;
+use crate::distribution::Distribution;
+use crate::probability::Probability;
 use derive_builder::Builder;
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
 pub struct Save {
     pub value: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub bonus: i16,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub reroll: Option<RerollType>,
 }
 impl_characteristic!(Save, value, bonus, -);
 impl_reroll!(Save, reroll);
 
-#[derive(Debug, Builder)]
+impl Save {
+    /// The save value used to represent no save at all ("-").
+    pub const NONE: u8 = 7;
+    /// Every save characteristic, from 2+ down to no save.
+    pub const ALL: [u8; 6] = [2, 3, 4, 5, 6, Self::NONE];
+}
+
+impl std::fmt::Display for Save {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.value >= Self::NONE {
+            write!(f, "-")
+        } else {
+            write!(f, "{}+", self.value)
+        }
+    }
+}
+
+#[derive(Debug, Builder, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
+// The bindings export a `Target` class, so the profile is named differently
+#[cfg_attr(feature = "tsify", serde(rename = "TargetProfile"))]
 pub struct Target {
     #[builder(setter(into), default)]
     pub save: Save,
     #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub ethereal: bool,
+    #[builder(setter(strip_option), default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ward: Option<u8>,
+    /// The health characteristic of each model.
+    #[builder(default = "1")]
+    #[cfg_attr(feature = "serde", serde(default = "one"))]
+    pub health: u32,
+    /// The number of models in the target unit.
+    #[builder(default = "1")]
+    #[cfg_attr(feature = "serde", serde(default = "one"))]
+    pub models: u32,
+}
+
+#[cfg(feature = "serde")]
+const fn one() -> u32 {
+    1
 }
 
 impl Target {
-    pub(crate) fn average_saved(&self, rend: u8) -> f64 {
-        let target = self.save - rend as i16;
+    /// A copy of this target with its save characteristic replaced by `value`.
+    pub fn with_save(&self, value: u8) -> Self {
+        Self {
+            save: Save { value, ..self.save },
+            ..self.clone()
+        }
+    }
+
+    /// The roll needed to save against an attack with the given rend.
+    pub fn save_target(&self, rend: u8) -> u8 {
+        self.save_roll(rend).modified()
+    }
+
+    /// The save rolled against an attack with the given rend, which ignores every
+    /// modifier if the target is ethereal.
+    pub(crate) fn save_roll(&self, rend: u8) -> Save {
         if self.ethereal {
-            D6.probability(target.unmodified())
+            Save {
+                bonus: 0,
+                ..self.save
+            }
         } else {
-            D6.probability(target.modified())
+            self.save - rend as i16
+        }
+    }
+
+    /// The probability that an attack with the given rend is saved (including any
+    /// rerolls).
+    pub fn average_saved(&self, rend: u8) -> f64 {
+        self.average_saved_in(rend)
+    }
+
+    pub fn average_unsaved(&self, rend: u8) -> f64 {
+        self.average_unsaved_in(rend)
+    }
+
+    /// The probability that a single point of damage is negated by the ward.
+    pub fn average_warded(&self) -> f64 {
+        self.average_warded_in()
+    }
+
+    pub fn average_unwarded(&self) -> f64 {
+        self.average_unwarded_in()
+    }
+
+    /// The probability that an attack with the given rend is saved, calculated with
+    /// any kind of [`Probability`].
+    pub fn average_saved_in<P: Probability>(&self, rend: u8) -> P {
+        let save = self.save_roll(rend);
+        let target = save.modified();
+        let success: P = D6.chance(target);
+        let rerolled = (1..=6)
+            .filter(|&face| face < target && save.rerolls(face))
+            .count();
+        success.clone() + P::ratio(rerolled as u64, 6) * success
+    }
+
+    pub fn average_unsaved_in<P: Probability>(&self, rend: u8) -> P {
+        P::one() - self.average_saved_in(rend)
+    }
+
+    /// The probability that a single point of damage is negated by the ward,
+    /// calculated with any kind of [`Probability`].
+    pub fn average_warded_in<P: Probability>(&self) -> P {
+        self.ward.map_or_else(P::zero, |ward| D6.chance(ward))
+    }
+
+    pub fn average_unwarded_in<P: Probability>(&self) -> P {
+        P::one() - self.average_warded_in()
+    }
+
+    /// The distribution of the number of models slain by the given damage (which
+    /// should already include any ward saves). Damage is allocated one model at a
+    /// time, with any excess carrying over to the next model.
+    pub fn models_slain<P: Probability>(&self, damage: &Distribution<P>) -> Distribution<P> {
+        let health = self.health.max(1) as usize;
+        let models = self.models as usize;
+        damage.map(|damage| (damage / health).min(models))
+    }
+
+    /// The probability that the given damage slays at least `models` models.
+    pub fn kill_probability<P: Probability>(&self, damage: &Distribution<P>, models: u32) -> P {
+        if models > self.models {
+            return P::zero();
         }
+        damage.at_least(models as usize * self.health.max(1) as usize)
     }
 
-    pub(crate) fn average_unsaved(&self, rend: u8) -> f64 {
-        1.0 - self.average_saved(rend)
+    /// The probability that the given damage slays every model in the unit.
+    pub fn destroy_probability<P: Probability>(&self, damage: &Distribution<P>) -> P {
+        self.kill_probability(damage, self.models)
     }
 }
 
@@ -43,6 +167,9 @@ where
         Self {
             save: save.into(),
             ethereal: false,
+            ward: None,
+            health: 1,
+            models: 1,
         }
     }
 }
@@ -68,6 +195,8 @@ impl TargetBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "exact")]
+    use crate::probability::Exact;
     use approx::assert_relative_eq;
 
     const MAX_RELATIVE: f64 = 0.001;
@@ -77,6 +206,9 @@ mod tests {
         let target = Target {
             save: 4.into(),
             ethereal: false,
+            ward: None,
+            health: 1,
+            models: 1,
         };
         assert_relative_eq!(target.average_saved(0), 0.5);
     }
@@ -86,6 +218,9 @@ mod tests {
         let target = Target {
             save: 4.into(),
             ethereal: false,
+            ward: None,
+            health: 1,
+            models: 1,
         };
         assert_relative_eq!(target.average_unsaved(0), 0.5);
     }
@@ -95,6 +230,9 @@ mod tests {
         let target = Target {
             save: 4.into(),
             ethereal: false,
+            ward: None,
+            health: 1,
+            models: 1,
         };
         assert_relative_eq!(target.average_saved(1), 0.333, max_relative = MAX_RELATIVE);
     }
@@ -108,6 +246,9 @@ mod tests {
                 reroll: None,
             },
             ethereal: false,
+            ward: None,
+            health: 1,
+            models: 1,
         };
         assert_relative_eq!(target.average_saved(1), 0.5);
     }
@@ -121,6 +262,9 @@ mod tests {
                 reroll: None,
             },
             ethereal: true,
+            ward: None,
+            health: 1,
+            models: 1,
         };
         assert_relative_eq!(target.average_saved(1), 0.5);
     }
@@ -130,6 +274,9 @@ mod tests {
         let target = Target {
             save: 4.into(),
             ethereal: true,
+            ward: None,
+            health: 1,
+            models: 1,
         };
         assert_relative_eq!(target.average_saved(1), 0.5);
     }
@@ -139,7 +286,171 @@ mod tests {
         let target = Target {
             save: 4.into(),
             ethereal: true,
+            ward: None,
+            health: 1,
+            models: 1,
         };
         assert_relative_eq!(target.average_saved(1), 0.5);
     }
+
+    #[test]
+    fn test_average_saved_with_rerolls() {
+        let target = TargetBuilder::default()
+            .save(4)
+            .reroll(RerollType::Ones)
+            .build()
+            .unwrap();
+        assert_relative_eq!(target.average_saved(0), 7.0 / 12.0);
+        let target = TargetBuilder::default()
+            .save(4)
+            .reroll(RerollType::Failed)
+            .build()
+            .unwrap();
+        assert_relative_eq!(target.average_saved(0), 0.75);
+        assert_relative_eq!(target.average_saved(1), 0.5);
+        // A 1+ save can't fail, so there is nothing to reroll
+        let target = TargetBuilder::default()
+            .save(2)
+            .bonus(1)
+            .reroll(RerollType::Ones)
+            .build()
+            .unwrap();
+        assert_relative_eq!(target.average_saved(0), 1.0);
+        assert_relative_eq!(target.average_saved(1), 35.0 / 36.0);
+    }
+
+    #[test]
+    fn test_average_warded() {
+        let mut target = Target {
+            save: 4.into(),
+            ethereal: false,
+            ward: None,
+            health: 1,
+            models: 1,
+        };
+        assert_relative_eq!(target.average_warded(), 0.0);
+        assert_relative_eq!(target.average_unwarded(), 1.0);
+        target.ward = Some(5);
+        assert_relative_eq!(target.average_warded(), 0.333, max_relative = MAX_RELATIVE);
+        assert_relative_eq!(
+            target.average_unwarded(),
+            0.667,
+            max_relative = MAX_RELATIVE
+        );
+    }
+
+    #[test]
+    fn test_models_slain_carries_damage_over() {
+        let target = TargetBuilder::default()
+            .save(4)
+            .health(2)
+            .models(3)
+            .build()
+            .unwrap();
+        let damage = Distribution::mixture([
+            (0.5, Distribution::certain(3)),
+            (0.5, Distribution::certain(9)),
+        ]);
+        let slain = target.models_slain(&damage);
+        assert_eq!(slain.pmf(), &[0.0, 0.5, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn test_kill_probability() {
+        let target = TargetBuilder::default()
+            .save(4)
+            .health(2)
+            .models(2)
+            .build()
+            .unwrap();
+        let damage = Distribution::binomial(4, 0.5);
+        assert_relative_eq!(target.kill_probability(&damage, 0), 1.0);
+        assert_relative_eq!(target.kill_probability(&damage, 1), 11.0 / 16.0);
+        assert_relative_eq!(target.destroy_probability(&damage), 1.0 / 16.0);
+        assert_relative_eq!(target.kill_probability(&damage, 3), 0.0);
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn exact_averages() {
+        let target = TargetBuilder::default().save(4).build().unwrap();
+        assert_eq!(target.average_saved_in::<Exact>(0), Exact::ratio(1, 2));
+        assert_eq!(target.average_unsaved_in::<Exact>(0), Exact::ratio(1, 2));
+        assert_eq!(target.average_saved_in::<Exact>(1), Exact::ratio(1, 3));
+        assert_eq!(target.average_warded_in::<Exact>(), Exact::zero());
+        assert_eq!(target.average_unwarded_in::<Exact>(), Exact::one());
+
+        let target = TargetBuilder::default()
+            .save(4)
+            .bonus(1)
+            .ward(5)
+            .build()
+            .unwrap();
+        assert_eq!(target.average_saved_in::<Exact>(1), Exact::ratio(1, 2));
+        assert_eq!(target.average_warded_in::<Exact>(), Exact::ratio(1, 3));
+        assert_eq!(target.average_unwarded_in::<Exact>(), Exact::ratio(2, 3));
+        let ethereal = Target {
+            ethereal: true,
+            ..target
+        };
+        assert_eq!(ethereal.average_saved_in::<Exact>(2), Exact::ratio(1, 2));
+
+        let target = TargetBuilder::default()
+            .save(4)
+            .reroll(RerollType::Ones)
+            .build()
+            .unwrap();
+        assert_eq!(target.average_saved_in::<Exact>(0), Exact::ratio(7, 12));
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn exact_models_slain_and_kill_probability() {
+        let target = TargetBuilder::default()
+            .save(4)
+            .health(2)
+            .models(3)
+            .build()
+            .unwrap();
+        let half = Exact::ratio(1, 2);
+        let damage = Distribution::mixture([
+            (half.clone(), Distribution::certain(3)),
+            (half.clone(), Distribution::certain(9)),
+        ]);
+        let slain = target.models_slain(&damage);
+        assert_eq!(
+            slain.pmf(),
+            &[Exact::zero(), half.clone(), Exact::zero(), half]
+        );
+
+        let target = Target {
+            models: 2,
+            ..target
+        };
+        let damage = Distribution::binomial(4, Exact::ratio(1, 2));
+        assert_eq!(target.kill_probability(&damage, 0), Exact::one());
+        assert_eq!(target.kill_probability(&damage, 1), Exact::ratio(11, 16));
+        assert_eq!(target.destroy_probability(&damage), Exact::ratio(1, 16));
+        assert_eq!(target.kill_probability(&damage, 3), Exact::zero());
+    }
+
+    #[test]
+    fn test_save_display() {
+        assert_eq!(Save::from(4).to_string(), "4+");
+        assert_eq!(Save::from(Save::NONE).to_string(), "-");
+    }
+
+    #[test]
+    fn test_with_save_keeps_modifiers() {
+        let target = TargetBuilder::default()
+            .save(4)
+            .bonus(1)
+            .ward(6)
+            .build()
+            .unwrap();
+        let target = target.with_save(Save::NONE);
+        assert_eq!(target.save.value, Save::NONE);
+        assert_eq!(target.save.bonus, 1);
+        assert_eq!(target.ward, Some(6));
+    }
 }
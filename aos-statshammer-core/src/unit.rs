@@ -0,0 +1,166 @@
+use crate::distribution::Distribution;
+use crate::probability::Probability;
+use crate::target::Target;
+use crate::weapon::Weapon;
+
+/// A weapon carried by some of the models in a [`Unit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnitWeapon {
+    pub weapon: Weapon,
+    /// The number of models attacking with this weapon.
+    pub models: u32,
+}
+
+/// A unit of models, each attacking with some of the unit's weapons.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Unit {
+    pub models: u32,
+    pub weapons: Vec<UnitWeapon>,
+}
+
+impl Unit {
+    /// This unit after losing models, down to `models`. Each weapon loses models in
+    /// proportion (rounding to the nearest model), as which models are removed as
+    /// casualties is up to the player.
+    pub fn with_models(&self, models: u32) -> Self {
+        let models = models.min(self.models);
+        let weapons = self
+            .weapons
+            .iter()
+            .map(|w| UnitWeapon {
+                weapon: w.weapon.clone(),
+                models: match self.models {
+                    0 => 0,
+                    total => (2 * w.models * models + total) / (2 * total),
+                },
+            })
+            .collect();
+        Self { models, weapons }
+    }
+
+    /// The average damage dealt to `target` when every model attacks.
+    pub fn average_damage(&self, target: &Target) -> f64 {
+        self.average_damage_in(target)
+    }
+
+    /// The average damage dealt to `target` when every model attacks, calculated with
+    /// any kind of [`Probability`].
+    pub fn average_damage_in<P: Probability>(&self, target: &Target) -> P {
+        self.weapons.iter().fold(P::zero(), |total, w| {
+            total + w.weapon.average_damage_in::<P>(target) * P::ratio(w.models as u64, 1)
+        })
+    }
+
+    /// The exact distribution of the damage dealt to `target` when every model attacks.
+    pub fn damage_distribution(&self, target: &Target) -> Distribution {
+        self.damage_distribution_in(target)
+    }
+
+    /// The exact distribution of the damage dealt to `target` when every model attacks,
+    /// calculated with any kind of [`Probability`].
+    pub fn damage_distribution_in<P: Probability>(&self, target: &Target) -> Distribution<P> {
+        self.weapons
+            .iter()
+            .fold(Distribution::certain(0), |total, w| {
+                let per_model = w.weapon.damage_distribution_in(target);
+                total.add(&per_model.repeat(w.models as usize))
+            })
+    }
+
+    /// The distribution of the number of models slain in `target`.
+    pub fn models_slain(&self, target: &Target) -> Distribution {
+        target.models_slain(&self.damage_distribution(target))
+    }
+
+    /// The probability of slaying at least `models` models in `target`.
+    pub fn kill_probability(&self, target: &Target, models: u32) -> f64 {
+        self.kill_probability_in(target, models)
+    }
+
+    /// The probability of slaying at least `models` models in `target`, calculated
+    /// with any kind of [`Probability`].
+    pub fn kill_probability_in<P: Probability>(&self, target: &Target, models: u32) -> P {
+        target.kill_probability(&self.damage_distribution_in(target), models)
+    }
+
+    /// The probability of slaying every model in `target`.
+    pub fn destroy_probability(&self, target: &Target) -> f64 {
+        self.kill_probability(target, target.models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target::TargetBuilder;
+    use crate::weapon::WeaponBuilder;
+    use approx::assert_relative_eq;
+
+    fn unit() -> Unit {
+        let weapon = |attacks, damage| {
+            WeaponBuilder::default()
+                .attacks(attacks)
+                .hit(3)
+                .wound(3)
+                .rend(1)
+                .damage(damage)
+                .build()
+                .unwrap()
+        };
+        Unit {
+            models: 5,
+            weapons: vec![
+                UnitWeapon {
+                    weapon: weapon(2, 1),
+                    models: 4,
+                },
+                UnitWeapon {
+                    weapon: weapon(2, 2),
+                    models: 1,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn distribution_mean_matches_average() {
+        let unit = unit();
+        let target = TargetBuilder::default().save(4).ward(6).build().unwrap();
+        let dist = unit.damage_distribution(&target);
+        assert_relative_eq!(dist.pmf().iter().sum::<f64>(), 1.0, max_relative = 1e-9);
+        assert_relative_eq!(
+            dist.mean(),
+            unit.average_damage(&target),
+            max_relative = 1e-9
+        );
+        assert_eq!(dist.max(), 12);
+    }
+
+    #[test]
+    fn with_models_reduces_weapons_in_proportion() {
+        let models: Vec<_> = unit()
+            .with_models(3)
+            .weapons
+            .iter()
+            .map(|w| w.models)
+            .collect();
+        assert_eq!(models, [2, 1]);
+        assert_eq!(unit().with_models(9), unit());
+        assert!(unit().with_models(0).weapons.iter().all(|w| w.models == 0));
+    }
+
+    #[test]
+    fn models_slain_is_capped_by_target_models() {
+        let target = TargetBuilder::default()
+            .save(4)
+            .health(2)
+            .models(3)
+            .build()
+            .unwrap();
+        let slain = unit().models_slain(&target);
+        assert_eq!(slain.max(), 3);
+        assert!(slain.mean() < unit().average_damage(&target) / 2.0);
+    }
+}
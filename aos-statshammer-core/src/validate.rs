@@ -0,0 +1,171 @@
+//! Checks for values which are out of range for a weapon or target, for anything
+//! which builds them from untrusted input (such as the REST server and the
+//! WebAssembly bindings). Every problem is described using the names of the
+//! `serde` wire format, e.g. `weapon.hit.value`.
+
+use crate::Characteristic;
+use crate::characteristics::ExplodingAbility;
+use crate::target::{Save, Target};
+use crate::weapon::Weapon;
+use std::ops::RangeInclusive;
+
+/// Every problem with `weapon` (empty if it is valid).
+pub fn weapon(weapon: &Weapon) -> Vec<String> {
+    let mut problems = vec![];
+    if weapon.attacks.value == 0 {
+        problems.push("weapon.attacks.value must be at least 1".to_string());
+    }
+    for (name, value) in [("hit", weapon.hit.value), ("wound", weapon.wound.value)] {
+        if !(1..=6).contains(&value) {
+            problems.push(format!(
+                "weapon.{name}.value must be between 1 and 6 (got {value})"
+            ));
+        }
+    }
+    if weapon.damage.value == 0 {
+        problems.push("weapon.damage.value must be at least 1".to_string());
+    }
+    for (name, exploding) in [
+        ("hit", weapon.hit.exploding),
+        ("wound", weapon.wound.exploding),
+    ] {
+        problems.extend(exploding.and_then(|ab| exploding_problem(name, ab)));
+    }
+    let characteristics: [(&str, &dyn Characteristic); 5] = [
+        ("attacks", &weapon.attacks),
+        ("hit", &weapon.hit),
+        ("wound", &weapon.wound),
+        ("rend", &weapon.rend),
+        ("damage", &weapon.damage),
+    ];
+    for (name, characteristic) in characteristics {
+        problems.extend(bonus_problem(&format!("weapon.{name}"), characteristic));
+    }
+    problems
+}
+
+/// The bonus must leave the modified characteristic between 0 and 255.
+fn bonus_problem(name: &str, characteristic: &dyn Characteristic) -> Option<String> {
+    characteristic.checked_modified().is_none().then(|| {
+        format!(
+            "{name}.bonus is out of range, as the modified value must be between 0 and {} (got {} with a bonus of {})",
+            u8::MAX,
+            characteristic.unmodified(),
+            characteristic.bonus()
+        )
+    })
+}
+
+fn exploding_problem(name: &str, ability: ExplodingAbility) -> Option<String> {
+    (!(1..=6).contains(&ability.on)).then(|| {
+        format!(
+            "weapon.{name}.exploding.on must be between 1 and 6 (got {})",
+            ability.on
+        )
+    })
+}
+
+/// The wards which make sense, as a ward of 1+ would negate every point of damage.
+pub const WARDS: RangeInclusive<u8> = 2..=6;
+
+/// The problem with a ward of `ward` (if it is out of range), describing it as `name`.
+pub fn ward_problem(name: &str, ward: u8) -> Option<String> {
+    (!WARDS.contains(&ward)).then(|| {
+        format!(
+            "{name} must be between {} and {} (got {ward})",
+            WARDS.start(),
+            WARDS.end()
+        )
+    })
+}
+
+/// Every problem with `target` (empty if it is valid).
+pub fn target(target: &Target) -> Vec<String> {
+    let mut problems = vec![];
+    if !(1..=Save::NONE).contains(&target.save.value) {
+        problems.push(format!(
+            "target.save.value must be between 1 and {} (for no save), got {}",
+            Save::NONE,
+            target.save.value
+        ));
+    }
+    problems.extend(
+        target
+            .ward
+            .and_then(|ward| ward_problem("target.ward", ward)),
+    );
+    problems.extend(bonus_problem("target.save", &target.save));
+    if target.health == 0 {
+        problems.push("target.health must be at least 1".to_string());
+    }
+    if target.models == 0 {
+        problems.push("target.models must be at least 1".to_string());
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weapon::WeaponBuilder;
+
+    #[test]
+    fn weapon_problems() {
+        let mut weapon = WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap();
+        assert!(super::weapon(&weapon).is_empty());
+        weapon.hit.value = 0;
+        weapon.wound.exploding = Some(ExplodingAbility {
+            on: 7,
+            ..Default::default()
+        });
+        assert_eq!(
+            super::weapon(&weapon),
+            vec![
+                "weapon.hit.value must be between 1 and 6 (got 0)",
+                "weapon.wound.exploding.on must be between 1 and 6 (got 7)",
+            ]
+        );
+    }
+
+    #[test]
+    fn bonuses_must_stay_in_range() {
+        let mut weapon = WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap();
+        weapon.attacks.bonus = 253;
+        assert!(super::weapon(&weapon).is_empty());
+        weapon.attacks.bonus = 300;
+        weapon.hit.bonus = 4;
+        assert_eq!(
+            super::weapon(&weapon),
+            vec![
+                "weapon.attacks.bonus is out of range, as the modified value must be between 0 and 255 (got 2 with a bonus of 300)",
+                "weapon.hit.bonus is out of range, as the modified value must be between 0 and 255 (got 3 with a bonus of 4)",
+            ]
+        );
+    }
+
+    #[test]
+    fn target_problems() {
+        assert!(super::target(&Target::from(Save::NONE)).is_empty());
+        assert_eq!(super::target(&Target::from(8)).len(), 1);
+        let mut target = Target::from(4);
+        target.ward = Some(1);
+        assert_eq!(
+            super::target(&target),
+            vec!["target.ward must be between 2 and 6 (got 1)"]
+        );
+    }
+}
@@ -1,24 +1,38 @@
 use crate::RerollType;
 use crate::characteristics::*;
 use crate::dice::D6;
-use crate::target::Target;
+use crate::distribution::{Distribution, Summary};
+use crate::probability::Probability;
+use crate::target::{Save, Target};
 use derive_builder::Builder;
 
 pub use crate::characteristics::ExplodingAbility;
 
+/// The most damage a weapon can do for [`Weapon::damage_summary`] to calculate its
+/// distribution. Working out the distribution takes time proportional to the square
+/// of the most damage, so this keeps it to a fraction of a second.
+pub const MAX_SUMMARY_DAMAGE: u64 = 20_000;
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
 pub struct Attacks {
     pub value: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub bonus: i16,
 }
 impl_characteristic!(Attacks, value, bonus, +);
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
-
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
 pub struct Hit {
     pub value: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub bonus: i16,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub reroll: Option<RerollType>,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub exploding: Option<ExplodingAbility>,
 }
 impl_characteristic!(Hit, value, bonus, -);
@@ -26,10 +40,15 @@ impl_reroll!(Hit, reroll);
 impl_exploding!(Hit, exploding);
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
 pub struct Wound {
     pub value: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub bonus: i16,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub reroll: Option<RerollType>,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub exploding: Option<ExplodingAbility>,
 }
 impl_characteristic!(Wound, value, bonus, -);
@@ -37,20 +56,30 @@ impl_reroll!(Wound, reroll);
 impl_exploding!(Wound, exploding);
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
 pub struct Rend {
     pub value: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub bonus: i16,
 }
 impl_characteristic!(Rend, value, bonus, +);
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
 pub struct Damage {
     pub value: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub bonus: i16,
 }
 impl_characteristic!(Damage, value, bonus, +);
 
-#[derive(Debug, Builder, Eq, PartialEq)]
+#[derive(Debug, Builder, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
+// The WebAssembly bindings have a `Weapon` class of their own
+#[cfg_attr(feature = "tsify", serde(rename = "WeaponProfile"))]
 pub struct Weapon {
     #[builder(setter(into))]
     pub attacks: Attacks,
@@ -64,21 +93,479 @@ pub struct Weapon {
     pub damage: Damage,
 }
 
+/// The average number of dice, successes and damage at each stage of an attack sequence,
+/// as calculated by [`Weapon::breakdown`] (or [`Weapon::breakdown_in`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "tsify", derive(tsify::Tsify))]
+pub struct DamageBreakdown<P = f64> {
+    pub attacks: P,
+    /// Including any rerolls, and any extra rolls from exploding hits.
+    pub hit_rolls: P,
+    pub hits: P,
+    /// The hit rolls which trigger the exploding ability (0 without one).
+    pub critical_hits: P,
+    /// Including any rerolls, and any extra rolls from exploding wounds.
+    pub wound_rolls: P,
+    pub wounds: P,
+    /// The wound rolls which trigger the exploding ability (0 without one).
+    pub critical_wounds: P,
+    /// The wounds which are not saved.
+    pub unsaved: P,
+    /// The damage from the unsaved wounds, before the ward.
+    pub damage: P,
+    /// The damage negated by the ward.
+    pub warded: P,
+    /// The damage actually dealt, which is the same as [`Weapon::average_damage`].
+    pub final_damage: P,
+}
+
 impl Weapon {
     pub fn average_damage(&self, target: &Target) -> f64 {
-        let average_attacks = self.attacks.modified() as f64;
+        self.average_damage_in(target)
+    }
+
+    /// The average damage dealt to `target`, calculated with any kind of [`Probability`].
+    pub fn average_damage_in<P: Probability>(&self, target: &Target) -> P {
+        self.breakdown_in(target).final_damage
+    }
+
+    /// The average of every stage of the attack sequence against `target`.
+    pub fn breakdown(&self, target: &Target) -> DamageBreakdown {
+        self.breakdown_in(target)
+    }
+
+    /// The average of every stage of the attack sequence against `target`, calculated
+    /// with any kind of [`Probability`].
+    pub fn breakdown_in<P: Probability>(&self, target: &Target) -> DamageBreakdown<P> {
+        let average_attacks = P::ratio(self.attacks.modified() as u64, 1);
 
         let mut average_hit_rolls =
-            average_attacks + (average_attacks * self.hit.reroll_probability());
-        average_hit_rolls += average_attacks * self.hit.extra_probability();
-        let average_hits = average_hit_rolls * D6.probability(self.hit.modified());
+            average_attacks.clone() + (average_attacks.clone() * self.hit.reroll_chance());
+        average_hit_rolls += average_attacks.clone() * self.hit.extra_chance();
+        let average_hits = average_hit_rolls.clone() * D6.chance(self.hit.modified());
 
         let mut average_wound_rolls =
-            average_hits + (average_hits * self.wound.reroll_probability());
-        average_wound_rolls += average_hits * self.wound.extra_probability();
-        let average_wounds = average_wound_rolls * D6.probability(self.wound.modified());
+            average_hits.clone() + (average_hits.clone() * self.wound.reroll_chance());
+        average_wound_rolls += average_hits.clone() * self.wound.extra_chance();
+        let average_wounds = average_wound_rolls.clone() * D6.chance(self.wound.modified());
+
+        let average_successful =
+            average_wounds.clone() * target.average_unsaved_in(self.rend.modified());
+        let average_damage =
+            average_successful.clone() * P::ratio(self.damage.modified() as u64, 1);
+        let final_damage = average_damage.clone() * target.average_unwarded_in();
+        DamageBreakdown {
+            critical_hits: average_attacks.clone() * self.hit.exploding_chance(),
+            attacks: average_attacks,
+            hit_rolls: average_hit_rolls,
+            critical_wounds: average_hits.clone() * self.wound.exploding_chance(),
+            hits: average_hits,
+            wound_rolls: average_wound_rolls,
+            wounds: average_wounds,
+            unsaved: average_successful,
+            warded: average_damage.clone() - final_damage.clone(),
+            damage: average_damage,
+            final_damage,
+        }
+    }
+
+    /// The average damage against every save characteristic (see [`Save::ALL`]),
+    /// keeping any other modifiers from `target`.
+    pub fn save_table(&self, target: &Target) -> Vec<(Save, f64)> {
+        self.save_table_in(target)
+    }
+
+    /// [`Weapon::save_table`], calculated with any kind of [`Probability`].
+    pub fn save_table_in<P: Probability>(&self, target: &Target) -> Vec<(Save, P)> {
+        Save::ALL
+            .iter()
+            .map(|&value| {
+                let target = target.with_save(value);
+                (target.save, self.average_damage_in(&target))
+            })
+            .collect()
+    }
+
+    /// The exact distribution of the damage dealt to `target`.
+    pub fn damage_distribution(&self, target: &Target) -> Distribution {
+        self.damage_distribution_in(target)
+    }
+
+    /// The exact distribution of the damage dealt to `target`, calculated with any
+    /// kind of [`Probability`] (e.g. [`Exact`](crate::probability::Exact) fractions).
+    pub fn damage_distribution_in<P: Probability>(&self, target: &Target) -> Distribution<P> {
+        self.attack_distribution(target)
+            .repeat(self.attacks.modified() as usize)
+    }
+
+    /// The most damage the weapon can do to any target, from every attack hitting
+    /// and wounding (and triggering any exploding abilities).
+    pub fn max_damage(&self) -> u64 {
+        let rolls =
+            |exploding: Option<ExplodingAbility>| 1 + exploding.map_or(0, |ab| ab.extra as u64);
+        self.attacks.modified() as u64
+            * rolls(self.hit.exploding)
+            * rolls(self.wound.exploding)
+            * self.damage.modified() as u64
+    }
+
+    /// The statistics which describe the damage dealt to `target`, or `None` if the
+    /// weapon can do more than [`MAX_SUMMARY_DAMAGE`] (when only the average, from
+    /// [`Weapon::average_damage`], is quick to calculate).
+    pub fn damage_summary(&self, target: &Target) -> Option<Summary> {
+        (self.max_damage() <= MAX_SUMMARY_DAMAGE)
+            .then(|| self.damage_distribution(target).summary())
+    }
+
+    /// The distribution of the number of models slain in `target`.
+    pub fn models_slain(&self, target: &Target) -> Distribution {
+        target.models_slain(&self.damage_distribution(target))
+    }
+
+    /// The probability of slaying at least `models` models in `target`.
+    pub fn kill_probability(&self, target: &Target, models: u32) -> f64 {
+        self.kill_probability_in(target, models)
+    }
+
+    /// The probability of slaying at least `models` models in `target`, calculated
+    /// with any kind of [`Probability`].
+    pub fn kill_probability_in<P: Probability>(&self, target: &Target, models: u32) -> P {
+        target.kill_probability(&self.damage_distribution_in(target), models)
+    }
+
+    /// The probability of slaying every model in `target`.
+    pub fn destroy_probability(&self, target: &Target) -> f64 {
+        self.kill_probability(target, target.models)
+    }
+
+    /// The distribution of the damage dealt to `target` by a single attack.
+    pub(crate) fn attack_distribution<P: Probability>(&self, target: &Target) -> Distribution<P> {
+        let hits = roll_distribution(&self.hit);
+        let wounds = hits.compound(&roll_distribution(&self.wound));
+        let unsaved = Distribution::bernoulli(target.average_unsaved_in(self.rend.modified()));
+        let damage = Distribution::binomial(
+            self.damage.modified() as usize,
+            target.average_unwarded_in(),
+        );
+        wounds.compound(&unsaved).compound(&damage)
+    }
+}
+
+/// The number of successes from a single roll (including any rerolls and extra
+/// rolls from an exploding ability). Each face is considered separately, as the
+/// extra rolls only come from the faces which trigger the exploding ability.
+fn roll_distribution<P: Probability, C: Reroll + Exploding>(characteristic: &C) -> Distribution<P> {
+    let success: P = D6.chance(characteristic.modified());
+    let extra = characteristic
+        .exploding_ability()
+        .map_or(Distribution::certain(0), |ab| {
+            Distribution::binomial(ab.extra as usize, success.clone())
+        });
+    Distribution::mixture((1..=6).map(|face| {
+        let mut outcome = if face >= characteristic.modified() {
+            Distribution::certain(1)
+        } else if characteristic.rerolls(face) {
+            Distribution::bernoulli(success.clone())
+        } else {
+            Distribution::certain(0)
+        };
+        if characteristic.explodes(face) {
+            outcome = outcome.add(&extra);
+        }
+        (P::ratio(1, 6), outcome)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "exact")]
+    use crate::probability::Exact;
+    use crate::target::TargetBuilder;
+    use approx::assert_relative_eq;
+
+    fn weapon() -> Weapon {
+        WeaponBuilder::default()
+            .attacks(3)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(2)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn bonuses_out_of_range() {
+        let attacks = Attacks {
+            value: 2,
+            bonus: i16::MAX,
+        };
+        assert_eq!(attacks.modified(), u8::MAX);
+        assert_eq!(attacks.checked_modified(), None);
+        let hit = Hit::from(3) + 2;
+        assert_eq!(hit.checked_modified(), Some(1));
+        assert_eq!((hit + 2).checked_modified(), None);
+        assert_eq!((hit + 2).modified(), 0);
+    }
+
+    #[test]
+    fn distribution_mean_matches_average() {
+        let mut weapon = weapon();
+        let target = Target::from(4);
+        let dist = weapon.damage_distribution(&target);
+        assert_relative_eq!(dist.pmf().iter().sum::<f64>(), 1.0, max_relative = 1e-9);
+        assert_relative_eq!(
+            dist.mean(),
+            weapon.average_damage(&target),
+            max_relative = 1e-9
+        );
+        assert_eq!(dist.max(), 6);
+
+        weapon.hit.reroll = Some(RerollType::Ones);
+        weapon.wound.exploding = Some(ExplodingAbility::default());
+        let target = TargetBuilder::default().save(4).ward(5).build().unwrap();
+        let dist = weapon.damage_distribution(&target);
+        assert_relative_eq!(
+            dist.mean(),
+            weapon.average_damage(&target),
+            max_relative = 1e-9
+        );
+        assert_eq!(dist.max(), 12);
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn exact_distribution_mean_matches_average() {
+        let mut weapon = weapon();
+        weapon.hit.reroll = Some(RerollType::Failed);
+        weapon.wound.exploding = Some(ExplodingAbility::default());
+        let target = TargetBuilder::default().save(4).ward(5).build().unwrap();
+        let dist = weapon.damage_distribution_in::<Exact>(&target);
+        assert_eq!(dist.mean(), weapon.average_damage_in::<Exact>(&target));
+    }
+
+    #[test]
+    fn breakdown() {
+        let mut weapon = weapon();
+        weapon.hit.exploding = Some(ExplodingAbility::default());
+        let target = TargetBuilder::default().save(4).ward(6).build().unwrap();
+        let b = weapon.breakdown(&target);
+        assert_relative_eq!(b.attacks, 3.0);
+        assert_relative_eq!(b.critical_hits, 0.5);
+        assert_relative_eq!(b.hit_rolls, 3.5);
+        assert_relative_eq!(b.hits, 3.5 * 2.0 / 3.0);
+        assert_relative_eq!(b.wound_rolls, b.hits);
+        assert_relative_eq!(b.critical_wounds, 0.0);
+        assert_relative_eq!(b.wounds, b.hits / 2.0);
+        assert_relative_eq!(b.unsaved, b.wounds * 2.0 / 3.0);
+        assert_relative_eq!(b.damage, b.unsaved * 2.0);
+        assert_relative_eq!(b.warded, b.damage / 6.0);
+        assert_relative_eq!(b.final_damage, weapon.average_damage(&target));
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn exact_breakdown() {
+        let mut weapon = weapon();
+        weapon.hit.exploding = Some(ExplodingAbility::default());
+        let target = TargetBuilder::default().save(4).ward(6).build().unwrap();
+        let b = weapon.breakdown_in::<Exact>(&target);
+        assert_eq!(b.attacks, Exact::ratio(3, 1));
+        assert_eq!(b.critical_hits, Exact::ratio(1, 2));
+        assert_eq!(b.hit_rolls, Exact::ratio(7, 2));
+        assert_eq!(b.hits, Exact::ratio(7, 3));
+        assert_eq!(b.wound_rolls, b.hits);
+        assert_eq!(b.critical_wounds, Exact::zero());
+        assert_eq!(b.wounds, Exact::ratio(7, 6));
+        assert_eq!(b.unsaved, Exact::ratio(7, 9));
+        assert_eq!(b.damage, Exact::ratio(14, 9));
+        assert_eq!(b.warded, Exact::ratio(7, 27));
+        assert_eq!(b.final_damage, Exact::ratio(35, 27));
+        assert_eq!(b.final_damage, weapon.average_damage_in(&target));
+        assert_relative_eq!(weapon.average_damage(&target), 35.0 / 27.0);
+    }
+
+    #[test]
+    fn exploding_distribution() {
+        // A 6 is always a hit, so it can't be a miss with an extra hit
+        let mut weapon = WeaponBuilder::default()
+            .attacks(1)
+            .hit(4)
+            .wound(1)
+            .rend(0)
+            .damage(1)
+            .build()
+            .unwrap();
+        weapon.hit.exploding = Some(ExplodingAbility::default());
+        let dist = weapon.damage_distribution(&Target::from(7));
+        let expected = [1.0 / 2.0, 5.0 / 12.0, 1.0 / 12.0];
+        assert_eq!(dist.pmf().len(), expected.len());
+        for (p, expected) in dist.pmf().iter().zip(expected) {
+            assert_relative_eq!(*p, expected, max_relative = 1e-12);
+        }
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn exact_exploding_distribution() {
+        // A 6 is always a hit, so it can't be a miss with an extra hit
+        let mut weapon = WeaponBuilder::default()
+            .attacks(1)
+            .hit(4)
+            .wound(1)
+            .rend(0)
+            .damage(1)
+            .build()
+            .unwrap();
+        weapon.hit.exploding = Some(ExplodingAbility::default());
+        let dist = weapon.damage_distribution_in::<Exact>(&Target::from(7));
+        assert_eq!(
+            dist.pmf(),
+            &[Exact::ratio(1, 2), Exact::ratio(5, 12), Exact::ratio(1, 12)]
+        );
+    }
+
+    #[test]
+    fn kill_a_hero() {
+        let hero = TargetBuilder::default()
+            .save(4)
+            .ward(5)
+            .health(6)
+            .build()
+            .unwrap();
+        let weapon = weapon();
+        let dist = weapon.damage_distribution(&hero);
+        assert_relative_eq!(weapon.destroy_probability(&hero), dist.at_least(6));
+        assert!(weapon.destroy_probability(&hero) > 0.0);
+        assert_relative_eq!(weapon.kill_probability(&hero, 2), 0.0);
+        assert_relative_eq!(
+            weapon.models_slain(&hero).mean(),
+            weapon.destroy_probability(&hero)
+        );
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn exact_kill_a_hero() {
+        let hero = TargetBuilder::default()
+            .save(4)
+            .ward(5)
+            .health(6)
+            .build()
+            .unwrap();
+        let weapon = weapon();
+        let dist = weapon.damage_distribution_in::<Exact>(&hero);
+        let destroy = weapon.kill_probability_in::<Exact>(&hero, 1);
+        assert_eq!(destroy, dist.at_least(6));
+        // Every attack has to hit, wound and get through the save, and both points of
+        // damage through the ward
+        let unsaved = Exact::ratio(2, 3) * Exact::ratio(1, 2) * Exact::ratio(2, 3);
+        let unwarded = Exact::ratio(2, 3) * Exact::ratio(2, 3);
+        assert_eq!(destroy, (unsaved * unwarded).pow(3));
+        assert_eq!(weapon.kill_probability_in::<Exact>(&hero, 2), Exact::zero());
+        assert_eq!(hero.models_slain(&dist).mean(), destroy);
+        assert_relative_eq!(weapon.destroy_probability(&hero), destroy.to_f64());
+    }
+
+    #[test]
+    fn save_table() {
+        let weapon = weapon();
+        let table = weapon.save_table(&Target::from(4));
+        assert_eq!(table.len(), 6);
+        assert_eq!(table[0].0.to_string(), "2+");
+        assert_eq!(table[5].0.to_string(), "-");
+        assert_relative_eq!(table[2].1, weapon.average_damage(&Target::from(4)));
+        assert!(table.windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn exact_save_table() {
+        let table = weapon().save_table_in::<Exact>(&Target::from(4));
+        // 3 attacks * 2/3 hit * 1/2 wound * 2 damage = 2, times the chance to fail
+        // each save (2+ becomes 3+ with rend 1)
+        let expected = [2, 3, 4, 5, 6, 6].map(|sixths| Exact::ratio(2 * sixths, 6));
+        let averages: Vec<_> = table.into_iter().map(|(_, average)| average).collect();
+        assert_eq!(averages, expected);
+    }
+
+    #[test]
+    fn distribution_without_ward_is_multiple_of_damage() {
+        let dist = weapon().damage_distribution(&Target::from(4));
+        assert!(dist.pmf().iter().skip(1).step_by(2).all(|p| *p == 0.0));
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn exact_distribution() {
+        use crate::probability::Exact;
+
+        let target = Target::from(4);
+        let dist = weapon().damage_distribution_in::<Exact>(&target);
+        assert_eq!(dist.pmf().iter().cloned().sum::<Exact>(), Exact::one());
+        // 3 attacks * 2/3 hit * 1/2 wound * 2/3 unsaved * 2 damage
+        assert_eq!(dist.mean(), Exact::ratio(4, 3));
+        assert_eq!(dist.probability(0), Exact::ratio(343, 729));
+        assert_relative_eq!(
+            dist.to_f64().mean(),
+            weapon().average_damage(&target),
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn summaries_only_for_distributions_quick_to_calculate() {
+        let target = Target::from(4);
+        let summary = weapon().damage_summary(&target).unwrap();
+        assert_relative_eq!(summary.mean, weapon().average_damage(&target));
+        assert_eq!(weapon().max_damage(), 6);
+
+        let exploding = Some(ExplodingAbility {
+            on: 2,
+            unmodified: true,
+            extra: 20,
+        });
+        let mut weapon = WeaponBuilder::default()
+            .attacks(255)
+            .hit(3)
+            .wound(3)
+            .rend(0)
+            .damage(50)
+            .build()
+            .unwrap();
+        weapon.hit.exploding = exploding;
+        weapon.wound.exploding = exploding;
+        assert_eq!(weapon.max_damage(), 255 * 21 * 21 * 50);
+        assert_eq!(weapon.damage_summary(&target), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_wire_format() {
+        let mut profile = weapon();
+        profile.hit.reroll = Some(RerollType::Ones);
+        profile.wound.exploding = Some(ExplodingAbility::default());
+        let json = serde_json::to_value(&profile).unwrap();
+        assert_eq!(json["hit"]["reroll"], "ones");
+        assert_eq!(
+            json["wound"]["exploding"],
+            serde_json::json!({"on": 6, "unmodified": true, "extra": 1})
+        );
+        assert_eq!(serde_json::from_value::<Weapon>(json).unwrap(), profile);
+
+        let minimal = r#"{"attacks": {"value": 3}, "hit": {"value": 3}, "wound": {"value": 4},
+            "rend": {"value": 1}, "damage": {"value": 2}}"#;
+        assert_eq!(serde_json::from_str::<Weapon>(minimal).unwrap(), weapon());
+
+        let target: Target = serde_json::from_str(r#"{"save": {"value": 4}, "ward": 5}"#).unwrap();
+        assert_eq!(target.ward, Some(5));
+        assert!(!target.ethereal);
+        assert_eq!((target.health, target.models), (1, 1));
 
-        let average_successful = average_wounds * target.average_unsaved(self.rend.modified());
-        average_successful * self.damage.modified() as f64
+        let dist = profile.damage_distribution(&target);
+        let json = serde_json::to_string(&dist).unwrap();
+        assert_eq!(serde_json::from_str::<Distribution>(&json).unwrap(), dist);
     }
 }
@@ -0,0 +1,24 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Set this to also update the committed copy of the header in `include/`.
+const UPDATE_HEADER: &str = "AOS_STATSHAMMER_UPDATE_HEADER";
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("Unable to read cbindgen.toml");
+    let bindings = cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("Unable to generate the C header");
+    bindings.write_to_file(out_dir.join("aos_statshammer.h"));
+    if env::var_os(UPDATE_HEADER).is_some() {
+        bindings.write_to_file(crate_dir.join("include/aos_statshammer.h"));
+    }
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed={UPDATE_HEADER}");
+}
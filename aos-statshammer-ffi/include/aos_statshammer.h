@@ -0,0 +1,231 @@
+#ifndef AOS_STATSHAMMER_H
+#define AOS_STATSHAMMER_H
+
+/* Generated by cbindgen from aos-statshammer-ffi. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// No reroll.
+#define AOS_REROLL_NONE 0
+
+// Reroll rolls of 1.
+#define AOS_REROLL_ONES 1
+
+// Reroll failed rolls.
+#define AOS_REROLL_FAILED 2
+
+// Reroll any roll.
+#define AOS_REROLL_ANY 3
+
+// The number of rows in a save table (2+ down to no save).
+#define AOS_SAVE_TABLE_LEN 6
+
+// The result of every fallible function.
+typedef enum AosStatus {
+  AOS_STATUS_OK = 0,
+  // A required pointer argument was null
+  AOS_STATUS_NULL_POINTER = 1,
+  // A string argument was not valid UTF-8, or not valid JSON for the type
+  AOS_STATUS_INVALID_JSON = 2,
+  // A profile was missing a characteristic, or had an unknown value or a value
+  // out of range
+  AOS_STATUS_INVALID_PROFILE = 3,
+  // The output buffer was too small (the required length is still written)
+  AOS_STATUS_BUFFER_TOO_SMALL = 4,
+  // An unexpected internal error
+  AOS_STATUS_PANIC = 5,
+} AosStatus;
+
+// An opaque target, freed with [`aos_target_free`].
+typedef struct AosTarget AosTarget;
+
+// An opaque weapon, freed with [`aos_weapon_free`].
+typedef struct AosWeapon AosWeapon;
+
+// A characteristic with a value and a modifier (e.g. attacks, rend, damage).
+typedef struct AosCharacteristic {
+  uint8_t value;
+  int16_t bonus;
+} AosCharacteristic;
+
+// A characteristic which is rolled for (hit or wound).
+typedef struct AosRollCharacteristic {
+  uint8_t value;
+  int16_t bonus;
+  // One of the `AOS_REROLL_*` constants
+  uint8_t reroll;
+  // Whether the exploding ability (the `exploding_*` fields) is used
+  bool exploding;
+  uint8_t exploding_on;
+  bool exploding_unmodified;
+  uint8_t exploding_extra;
+} AosRollCharacteristic;
+
+typedef struct AosWeaponProfile {
+  struct AosCharacteristic attacks;
+  struct AosRollCharacteristic hit;
+  struct AosRollCharacteristic wound;
+  struct AosCharacteristic rend;
+  struct AosCharacteristic damage;
+} AosWeaponProfile;
+
+typedef struct AosTargetProfile {
+  // The save characteristic (7 for no save)
+  uint8_t save;
+  int16_t save_bonus;
+  // One of the `AOS_REROLL_*` constants
+  uint8_t save_reroll;
+  bool ethereal;
+  // The ward characteristic (0 for no ward)
+  uint8_t ward;
+} AosTargetProfile;
+
+// The statistics which describe a damage distribution.
+typedef struct AosSummary {
+  double mean;
+  double std_dev;
+  // The standard deviation relative to the mean (NaN if the mean is zero)
+  double coefficient_of_variation;
+  // The 10th percentile (9 times in 10 the damage is at least this)
+  size_t p10;
+  // The median
+  size_t p50;
+  // The 90th percentile (only 1 time in 10 the damage is more than this)
+  size_t p90;
+  // The probability of no damage
+  double zero;
+} AosSummary;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The message for the last error on this thread, or null if the last call
+// succeeded. The string is owned by the library and is valid until the next
+// call on the same thread.
+const char *aos_last_error_message(void);
+
+// Build a weapon from a profile, writing it to `out_weapon`.
+//
+// # Safety
+//
+// `profile` must be null or a valid pointer, and `out_weapon` must be null or
+// valid for writes.
+enum AosStatus aos_weapon_new(const struct AosWeaponProfile *profile,
+                              struct AosWeapon **out_weapon);
+
+// Build a weapon from JSON (in the core `serde` wire format), writing it to
+// `out_weapon`.
+//
+// # Safety
+//
+// `json` must be null or a nul terminated string, and `out_weapon` must be
+// null or valid for writes.
+enum AosStatus aos_weapon_from_json(const char *json, struct AosWeapon **out_weapon);
+
+// Free a weapon. Passing null does nothing.
+//
+// # Safety
+//
+// `weapon` must be null or a weapon created by this library which has not
+// already been freed.
+void aos_weapon_free(struct AosWeapon *weapon);
+
+// Build a target from a profile, writing it to `out_target`.
+//
+// # Safety
+//
+// `profile` must be null or a valid pointer, and `out_target` must be null or
+// valid for writes.
+enum AosStatus aos_target_new(const struct AosTargetProfile *profile,
+                              struct AosTarget **out_target);
+
+// Build a target from JSON (in the core `serde` wire format), writing it to
+// `out_target`.
+//
+// # Safety
+//
+// `json` must be null or a nul terminated string, and `out_target` must be
+// null or valid for writes.
+enum AosStatus aos_target_from_json(const char *json, struct AosTarget **out_target);
+
+// Free a target. Passing null does nothing.
+//
+// # Safety
+//
+// `target` must be null or a target created by this library which has not
+// already been freed.
+void aos_target_free(struct AosTarget *target);
+
+// The average damage of `weapon` against `target`.
+//
+// # Safety
+//
+// `weapon` and `target` must be null or valid, and `out_damage` must be null
+// or valid for writes.
+enum AosStatus aos_average_damage(const struct AosWeapon *weapon,
+                                  const struct AosTarget *target,
+                                  double *out_damage);
+
+// The statistics which describe the damage dealt by `weapon` to `target`.
+//
+// # Safety
+//
+// `weapon` and `target` must be null or valid, and `out_summary` must be null
+// or valid for writes.
+enum AosStatus aos_damage_summary(const struct AosWeapon *weapon,
+                                  const struct AosTarget *target,
+                                  struct AosSummary *out_summary);
+
+// The exact damage distribution of `weapon` against `target`, where
+// `out_probabilities[k]` is `P(damage = k)`.
+//
+// The full length of the distribution is always written to `out_len`. If it
+// is more than `capacity`, nothing is written to `out_probabilities` and
+// [`AosStatus::BufferTooSmall`] is returned (so the length can be queried by
+// passing a capacity of 0).
+//
+// # Safety
+//
+// `weapon` and `target` must be null or valid, `out_len` must be null or valid
+// for writes, and `out_probabilities` must be valid for `capacity` writes (it
+// may be null if `capacity` is 0).
+enum AosStatus aos_damage_distribution(const struct AosWeapon *weapon,
+                                       const struct AosTarget *target,
+                                       double *out_probabilities,
+                                       size_t capacity,
+                                       size_t *out_len);
+
+// The average damage against every save from 2+ down to no save, written to
+// `out_damage` (which must have room for [`AOS_SAVE_TABLE_LEN`] values). Only
+// the modifiers of `target` are used, and it may be null for no modifiers.
+//
+// # Safety
+//
+// `weapon` and `target` must be null or valid, and `out_damage` must be null
+// or valid for [`AOS_SAVE_TABLE_LEN`] writes.
+enum AosStatus aos_save_table(const struct AosWeapon *weapon,
+                              const struct AosTarget *target,
+                              double *out_damage);
+
+// The statistics which describe the damage against every save from 2+ down to no
+// save, in the same order as [`aos_save_table`]. Only the modifiers of `target`
+// are used, and it may be null for no modifiers.
+//
+// # Safety
+//
+// `weapon` and `target` must be null or valid, and `out_summaries` must be null
+// or valid for [`AOS_SAVE_TABLE_LEN`] writes.
+enum AosStatus aos_save_table_summary(const struct AosWeapon *weapon,
+                                      const struct AosTarget *target,
+                                      struct AosSummary *out_summaries);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AOS_STATSHAMMER_H */
@@ -0,0 +1,661 @@
+//! A C ABI for the core calculations, for embedding in other tools.
+//!
+//! Every fallible function returns an [`AosStatus`], writing its result through
+//! an out pointer. When the status is not [`AosStatus::Ok`], a description of
+//! the error can be read with [`aos_last_error_message`]. Panics are caught
+//! and reported as [`AosStatus::Panic`] rather than unwinding into the caller.
+//!
+//! The C header is generated into `OUT_DIR` by the build script. The copy in
+//! `include/aos_statshammer.h` is only updated when `AOS_STATSHAMMER_UPDATE_HEADER`
+//! is set (and the tests check that it is current).
+
+use aos_statshammer_core::distribution::Summary;
+use aos_statshammer_core::target::{Save, Target, TargetBuilder};
+use aos_statshammer_core::weapon::{self, ExplodingAbility, Weapon, WeaponBuilder};
+use aos_statshammer_core::{RerollType, validate};
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+/// No reroll.
+pub const AOS_REROLL_NONE: u8 = 0;
+/// Reroll rolls of 1.
+pub const AOS_REROLL_ONES: u8 = 1;
+/// Reroll failed rolls.
+pub const AOS_REROLL_FAILED: u8 = 2;
+/// Reroll any roll.
+pub const AOS_REROLL_ANY: u8 = 3;
+
+/// The number of rows in a save table (2+ down to no save).
+pub const AOS_SAVE_TABLE_LEN: usize = 6;
+const _: () = assert!(AOS_SAVE_TABLE_LEN == Save::ALL.len());
+
+/// The result of every fallible function.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum AosStatus {
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8, or not valid JSON for the type
+    InvalidJson = 2,
+    /// A profile was missing a characteristic, or had an unknown value or a value
+    /// out of range
+    InvalidProfile = 3,
+    /// The output buffer was too small (the required length is still written)
+    BufferTooSmall = 4,
+    /// An unexpected internal error
+    Panic = 5,
+}
+
+/// A characteristic with a value and a modifier (e.g. attacks, rend, damage).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AosCharacteristic {
+    pub value: u8,
+    pub bonus: i16,
+}
+
+/// A characteristic which is rolled for (hit or wound).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AosRollCharacteristic {
+    pub value: u8,
+    pub bonus: i16,
+    /// One of the `AOS_REROLL_*` constants
+    pub reroll: u8,
+    /// Whether the exploding ability (the `exploding_*` fields) is used
+    pub exploding: bool,
+    pub exploding_on: u8,
+    pub exploding_unmodified: bool,
+    pub exploding_extra: u8,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AosWeaponProfile {
+    pub attacks: AosCharacteristic,
+    pub hit: AosRollCharacteristic,
+    pub wound: AosRollCharacteristic,
+    pub rend: AosCharacteristic,
+    pub damage: AosCharacteristic,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct AosTargetProfile {
+    /// The save characteristic (7 for no save)
+    pub save: u8,
+    pub save_bonus: i16,
+    /// One of the `AOS_REROLL_*` constants
+    pub save_reroll: u8,
+    pub ethereal: bool,
+    /// The ward characteristic (0 for no ward)
+    pub ward: u8,
+}
+
+/// The statistics which describe a damage distribution.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct AosSummary {
+    pub mean: f64,
+    pub std_dev: f64,
+    /// The standard deviation relative to the mean (NaN if the mean is zero)
+    pub coefficient_of_variation: f64,
+    /// The 10th percentile (9 times in 10 the damage is at least this)
+    pub p10: usize,
+    /// The median
+    pub p50: usize,
+    /// The 90th percentile (only 1 time in 10 the damage is more than this)
+    pub p90: usize,
+    /// The probability of no damage
+    pub zero: f64,
+}
+
+impl From<Summary> for AosSummary {
+    fn from(summary: Summary) -> Self {
+        Self {
+            mean: summary.mean,
+            std_dev: summary.std_dev,
+            coefficient_of_variation: summary.coefficient_of_variation.unwrap_or(f64::NAN),
+            p10: summary.p10,
+            p50: summary.p50,
+            p90: summary.p90,
+            zero: summary.zero,
+        }
+    }
+}
+
+/// An opaque weapon, freed with [`aos_weapon_free`].
+pub struct AosWeapon(Weapon);
+
+/// An opaque target, freed with [`aos_target_free`].
+pub struct AosTarget(Target);
+
+struct Error {
+    status: AosStatus,
+    message: String,
+}
+
+impl Error {
+    fn new(status: AosStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Run `f`, recording any error (or panic) as the last error.
+fn ffi(f: impl FnOnce() -> Result<(), Error>) -> AosStatus {
+    let error = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => None,
+        Ok(Err(error)) => Some(error),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Some(Error::new(AosStatus::Panic, message))
+        }
+    };
+    let status = error.as_ref().map_or(AosStatus::Ok, |e| e.status);
+    LAST_ERROR.with(|last| {
+        *last.borrow_mut() = error
+            .map(|e| CString::new(e.message.replace('\0', "")).expect("nul bytes are removed"));
+    });
+    status
+}
+
+fn non_null<'a, T>(pointer: *const T, name: &str) -> Result<&'a T, Error> {
+    // SAFETY: the caller guarantees that any non-null pointer is valid
+    unsafe { pointer.as_ref() }
+        .ok_or_else(|| Error::new(AosStatus::NullPointer, format!("{name} is null")))
+}
+
+fn out<'a, T>(pointer: *mut T, name: &str) -> Result<&'a mut T, Error> {
+    // SAFETY: the caller guarantees that any non-null pointer is valid
+    unsafe { pointer.as_mut() }
+        .ok_or_else(|| Error::new(AosStatus::NullPointer, format!("{name} is null")))
+}
+
+fn from_json<T: DeserializeOwned>(json: *const c_char) -> Result<T, Error> {
+    non_null(json, "json")?;
+    // SAFETY: the caller guarantees that `json` is a nul terminated string
+    let json = unsafe { CStr::from_ptr(json) }
+        .to_str()
+        .map_err(|err| Error::new(AosStatus::InvalidJson, err.to_string()))?;
+    serde_json::from_str(json).map_err(|err| Error::new(AosStatus::InvalidJson, err.to_string()))
+}
+
+/// An error listing every problem (using the names of the core wire format), if
+/// there are any.
+fn valid(problems: Vec<String>) -> Result<(), Error> {
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::new(AosStatus::InvalidProfile, problems.join("; ")))
+    }
+}
+
+fn valid_weapon(weapon: Weapon) -> Result<Weapon, Error> {
+    valid(validate::weapon(&weapon)).map(|()| weapon)
+}
+
+fn valid_target(target: Target) -> Result<Target, Error> {
+    valid(validate::target(&target)).map(|()| target)
+}
+
+fn reroll(value: u8) -> Result<Option<RerollType>, Error> {
+    match value {
+        AOS_REROLL_NONE => Ok(None),
+        AOS_REROLL_ONES => Ok(Some(RerollType::Ones)),
+        AOS_REROLL_FAILED => Ok(Some(RerollType::Failed)),
+        AOS_REROLL_ANY => Ok(Some(RerollType::Any)),
+        other => Err(Error::new(
+            AosStatus::InvalidProfile,
+            format!("unknown reroll type {other}"),
+        )),
+    }
+}
+
+impl AosRollCharacteristic {
+    fn exploding(&self) -> Option<ExplodingAbility> {
+        self.exploding.then_some(ExplodingAbility {
+            on: self.exploding_on,
+            unmodified: self.exploding_unmodified,
+            extra: self.exploding_extra,
+        })
+    }
+}
+
+impl AosWeaponProfile {
+    fn build(&self) -> Result<Weapon, Error> {
+        WeaponBuilder::default()
+            .attacks(weapon::Attacks {
+                value: self.attacks.value,
+                bonus: self.attacks.bonus,
+            })
+            .hit(weapon::Hit {
+                value: self.hit.value,
+                bonus: self.hit.bonus,
+                reroll: reroll(self.hit.reroll)?,
+                exploding: self.hit.exploding(),
+            })
+            .wound(weapon::Wound {
+                value: self.wound.value,
+                bonus: self.wound.bonus,
+                reroll: reroll(self.wound.reroll)?,
+                exploding: self.wound.exploding(),
+            })
+            .rend(weapon::Rend {
+                value: self.rend.value,
+                bonus: self.rend.bonus,
+            })
+            .damage(weapon::Damage {
+                value: self.damage.value,
+                bonus: self.damage.bonus,
+            })
+            .build()
+            .map_err(|err| Error::new(AosStatus::InvalidProfile, err.to_string()))
+            .and_then(valid_weapon)
+    }
+}
+
+impl AosTargetProfile {
+    fn build(&self) -> Result<Target, Error> {
+        let mut builder = TargetBuilder::default();
+        builder
+            .save(Save {
+                value: self.save,
+                bonus: self.save_bonus,
+                reroll: reroll(self.save_reroll)?,
+            })
+            .ethereal(self.ethereal);
+        if self.ward > 0 {
+            builder.ward(self.ward);
+        }
+        builder
+            .build()
+            .map_err(|err| Error::new(AosStatus::InvalidProfile, err.to_string()))
+            .and_then(valid_target)
+    }
+}
+
+/// The message for the last error on this thread, or null if the last call
+/// succeeded. The string is owned by the library and is valid until the next
+/// call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn aos_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// Build a weapon from a profile, writing it to `out_weapon`.
+///
+/// # Safety
+///
+/// `profile` must be null or a valid pointer, and `out_weapon` must be null or
+/// valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_weapon_new(
+    profile: *const AosWeaponProfile,
+    out_weapon: *mut *mut AosWeapon,
+) -> AosStatus {
+    ffi(|| {
+        let weapon = non_null(profile, "profile")?.build()?;
+        *out(out_weapon, "out_weapon")? = Box::into_raw(Box::new(AosWeapon(weapon)));
+        Ok(())
+    })
+}
+
+/// Build a weapon from JSON (in the core `serde` wire format), writing it to
+/// `out_weapon`.
+///
+/// # Safety
+///
+/// `json` must be null or a nul terminated string, and `out_weapon` must be
+/// null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_weapon_from_json(
+    json: *const c_char,
+    out_weapon: *mut *mut AosWeapon,
+) -> AosStatus {
+    ffi(|| {
+        let weapon = valid_weapon(from_json(json)?)?;
+        *out(out_weapon, "out_weapon")? = Box::into_raw(Box::new(AosWeapon(weapon)));
+        Ok(())
+    })
+}
+
+/// Free a weapon. Passing null does nothing.
+///
+/// # Safety
+///
+/// `weapon` must be null or a weapon created by this library which has not
+/// already been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_weapon_free(weapon: *mut AosWeapon) {
+    if !weapon.is_null() {
+        // SAFETY: the caller guarantees that `weapon` came from `Box::into_raw`
+        drop(unsafe { Box::from_raw(weapon) });
+    }
+}
+
+/// Build a target from a profile, writing it to `out_target`.
+///
+/// # Safety
+///
+/// `profile` must be null or a valid pointer, and `out_target` must be null or
+/// valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_target_new(
+    profile: *const AosTargetProfile,
+    out_target: *mut *mut AosTarget,
+) -> AosStatus {
+    ffi(|| {
+        let target = non_null(profile, "profile")?.build()?;
+        *out(out_target, "out_target")? = Box::into_raw(Box::new(AosTarget(target)));
+        Ok(())
+    })
+}
+
+/// Build a target from JSON (in the core `serde` wire format), writing it to
+/// `out_target`.
+///
+/// # Safety
+///
+/// `json` must be null or a nul terminated string, and `out_target` must be
+/// null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_target_from_json(
+    json: *const c_char,
+    out_target: *mut *mut AosTarget,
+) -> AosStatus {
+    ffi(|| {
+        let target = valid_target(from_json(json)?)?;
+        *out(out_target, "out_target")? = Box::into_raw(Box::new(AosTarget(target)));
+        Ok(())
+    })
+}
+
+/// Free a target. Passing null does nothing.
+///
+/// # Safety
+///
+/// `target` must be null or a target created by this library which has not
+/// already been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_target_free(target: *mut AosTarget) {
+    if !target.is_null() {
+        // SAFETY: the caller guarantees that `target` came from `Box::into_raw`
+        drop(unsafe { Box::from_raw(target) });
+    }
+}
+
+/// The average damage of `weapon` against `target`.
+///
+/// # Safety
+///
+/// `weapon` and `target` must be null or valid, and `out_damage` must be null
+/// or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_average_damage(
+    weapon: *const AosWeapon,
+    target: *const AosTarget,
+    out_damage: *mut f64,
+) -> AosStatus {
+    ffi(|| {
+        let weapon = &non_null(weapon, "weapon")?.0;
+        let target = &non_null(target, "target")?.0;
+        *out(out_damage, "out_damage")? = weapon.average_damage(target);
+        Ok(())
+    })
+}
+
+/// The statistics which describe the damage dealt by `weapon` to `target`.
+///
+/// # Safety
+///
+/// `weapon` and `target` must be null or valid, and `out_summary` must be null
+/// or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_damage_summary(
+    weapon: *const AosWeapon,
+    target: *const AosTarget,
+    out_summary: *mut AosSummary,
+) -> AosStatus {
+    ffi(|| {
+        let weapon = &non_null(weapon, "weapon")?.0;
+        let target = &non_null(target, "target")?.0;
+        *out(out_summary, "out_summary")? = weapon.damage_distribution(target).summary().into();
+        Ok(())
+    })
+}
+
+/// The exact damage distribution of `weapon` against `target`, where
+/// `out_probabilities[k]` is `P(damage = k)`.
+///
+/// The full length of the distribution is always written to `out_len`. If it
+/// is more than `capacity`, nothing is written to `out_probabilities` and
+/// [`AosStatus::BufferTooSmall`] is returned (so the length can be queried by
+/// passing a capacity of 0).
+///
+/// # Safety
+///
+/// `weapon` and `target` must be null or valid, `out_len` must be null or valid
+/// for writes, and `out_probabilities` must be valid for `capacity` writes (it
+/// may be null if `capacity` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_damage_distribution(
+    weapon: *const AosWeapon,
+    target: *const AosTarget,
+    out_probabilities: *mut f64,
+    capacity: usize,
+    out_len: *mut usize,
+) -> AosStatus {
+    ffi(|| {
+        let weapon = &non_null(weapon, "weapon")?.0;
+        let target = &non_null(target, "target")?.0;
+        let out_len = out(out_len, "out_len")?;
+        let dist = weapon.damage_distribution(target);
+        let pmf = dist.pmf();
+        *out_len = pmf.len();
+        if pmf.len() > capacity {
+            return Err(Error::new(
+                AosStatus::BufferTooSmall,
+                format!("the distribution needs {} values", pmf.len()),
+            ));
+        }
+        non_null(out_probabilities, "out_probabilities")?;
+        // SAFETY: the caller guarantees `capacity` (>= `pmf.len()`) writes are valid
+        unsafe { ptr::copy_nonoverlapping(pmf.as_ptr(), out_probabilities, pmf.len()) };
+        Ok(())
+    })
+}
+
+/// The average damage against every save from 2+ down to no save, written to
+/// `out_damage` (which must have room for [`AOS_SAVE_TABLE_LEN`] values). Only
+/// the modifiers of `target` are used, and it may be null for no modifiers.
+///
+/// # Safety
+///
+/// `weapon` and `target` must be null or valid, and `out_damage` must be null
+/// or valid for [`AOS_SAVE_TABLE_LEN`] writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_save_table(
+    weapon: *const AosWeapon,
+    target: *const AosTarget,
+    out_damage: *mut f64,
+) -> AosStatus {
+    ffi(|| {
+        let weapon = &non_null(weapon, "weapon")?.0;
+        // SAFETY: the caller guarantees that any non-null pointer is valid
+        let target =
+            unsafe { target.as_ref() }.map_or_else(|| Target::from(Save::NONE), |t| t.0.clone());
+        non_null(out_damage, "out_damage")?;
+        for (i, (_, damage)) in weapon.save_table(&target).into_iter().enumerate() {
+            // SAFETY: the caller guarantees `AOS_SAVE_TABLE_LEN` writes are valid
+            unsafe { out_damage.add(i).write(damage) };
+        }
+        Ok(())
+    })
+}
+
+/// The statistics which describe the damage against every save from 2+ down to no
+/// save, in the same order as [`aos_save_table`]. Only the modifiers of `target`
+/// are used, and it may be null for no modifiers.
+///
+/// # Safety
+///
+/// `weapon` and `target` must be null or valid, and `out_summaries` must be null
+/// or valid for [`AOS_SAVE_TABLE_LEN`] writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aos_save_table_summary(
+    weapon: *const AosWeapon,
+    target: *const AosTarget,
+    out_summaries: *mut AosSummary,
+) -> AosStatus {
+    ffi(|| {
+        let weapon = &non_null(weapon, "weapon")?.0;
+        // SAFETY: the caller guarantees that any non-null pointer is valid
+        let target =
+            unsafe { target.as_ref() }.map_or_else(|| Target::from(Save::NONE), |t| t.0.clone());
+        non_null(out_summaries, "out_summaries")?;
+        for (i, &save) in Save::ALL.iter().enumerate() {
+            // The summaries were asked for, so they're calculated however long it takes
+            let summary = weapon
+                .damage_distribution(&target.with_save(save))
+                .summary();
+            // SAFETY: the caller guarantees `AOS_SAVE_TABLE_LEN` writes are valid
+            unsafe { out_summaries.add(i).write(summary.into()) };
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> AosWeaponProfile {
+        AosWeaponProfile {
+            attacks: AosCharacteristic { value: 2, bonus: 0 },
+            hit: AosRollCharacteristic {
+                value: 3,
+                ..Default::default()
+            },
+            wound: AosRollCharacteristic {
+                value: 4,
+                ..Default::default()
+            },
+            rend: AosCharacteristic { value: 1, bonus: 0 },
+            damage: AosCharacteristic { value: 1, bonus: 0 },
+        }
+    }
+
+    fn last_error() -> String {
+        let message = aos_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn struct_and_json_profiles_match() {
+        let json = CString::new(
+            r#"{"attacks": {"value": 2}, "hit": {"value": 3}, "wound": {"value": 4},
+                "rend": {"value": 1}, "damage": {"value": 1}}"#,
+        )
+        .unwrap();
+        let mut from_struct = ptr::null_mut();
+        let mut from_json = ptr::null_mut();
+        unsafe {
+            assert_eq!(aos_weapon_new(&profile(), &mut from_struct), AosStatus::Ok);
+            assert_eq!(
+                aos_weapon_from_json(json.as_ptr(), &mut from_json),
+                AosStatus::Ok
+            );
+            assert_eq!((*from_struct).0, (*from_json).0);
+            aos_weapon_free(from_struct);
+            aos_weapon_free(from_json);
+        }
+        assert!(aos_last_error_message().is_null());
+    }
+
+    #[test]
+    fn errors_set_the_last_error() {
+        let mut weapon = ptr::null_mut();
+        let mut invalid = profile();
+        invalid.hit.reroll = 9;
+        unsafe {
+            assert_eq!(
+                aos_weapon_new(&invalid, &mut weapon),
+                AosStatus::InvalidProfile
+            );
+            assert_eq!(last_error(), "unknown reroll type 9");
+            invalid.hit.reroll = AOS_REROLL_NONE;
+            invalid.hit.value = 0;
+            assert_eq!(
+                aos_weapon_new(&invalid, &mut weapon),
+                AosStatus::InvalidProfile
+            );
+            assert_eq!(
+                last_error(),
+                "weapon.hit.value must be between 1 and 6 (got 0)"
+            );
+            assert_eq!(
+                aos_weapon_new(ptr::null(), &mut weapon),
+                AosStatus::NullPointer
+            );
+            assert_eq!(last_error(), "profile is null");
+            let json = CString::new("{").unwrap();
+            assert_eq!(
+                aos_weapon_from_json(json.as_ptr(), &mut weapon),
+                AosStatus::InvalidJson
+            );
+        }
+        assert!(weapon.is_null());
+    }
+
+    #[test]
+    fn targets_are_validated() {
+        let mut target = ptr::null_mut();
+        let profile = AosTargetProfile {
+            save: 4,
+            ward: 1,
+            ..Default::default()
+        };
+        // 0 is no ward in the struct, but through JSON it must be left out (or null)
+        let json = CString::new(r#"{"save": {"value": 4}, "ward": 0}"#).unwrap();
+        unsafe {
+            assert_eq!(
+                aos_target_new(&profile, &mut target),
+                AosStatus::InvalidProfile
+            );
+            assert_eq!(last_error(), "target.ward must be between 2 and 6 (got 1)");
+            assert_eq!(
+                aos_target_from_json(json.as_ptr(), &mut target),
+                AosStatus::InvalidProfile
+            );
+            assert_eq!(last_error(), "target.ward must be between 2 and 6 (got 0)");
+            let no_ward = AosTargetProfile { ward: 0, ..profile };
+            assert_eq!(aos_target_new(&no_ward, &mut target), AosStatus::Ok);
+            assert_eq!((*target).0.ward, None);
+            aos_target_free(target);
+        }
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        assert_eq!(ffi(|| panic!("oh no")), AosStatus::Panic);
+        assert_eq!(last_error(), "oh no");
+    }
+}
@@ -0,0 +1,101 @@
+/* Exercises the C API through the generated header. Run by `tests/c_api.rs`. */
+#include "aos_statshammer.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+static int failures = 0;
+
+#define CHECK(condition)                                                   \
+    do {                                                                   \
+        if (!(condition)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,         \
+                    __LINE__, #condition);                                 \
+            failures++;                                                    \
+        }                                                                  \
+    } while (0)
+
+static int close_to(double a, double b) { return fabs(a - b) < 1e-9; }
+
+int main(void) {
+    AosWeaponProfile profile = {0};
+    profile.attacks.value = 2;
+    profile.hit.value = 3;
+    profile.wound.value = 4;
+    profile.rend.value = 1;
+    profile.damage.value = 1;
+
+    AosWeapon *weapon = NULL;
+    CHECK(aos_weapon_new(&profile, &weapon) == AOS_STATUS_OK);
+    CHECK(weapon != NULL);
+    CHECK(aos_last_error_message() == NULL);
+
+    AosTargetProfile target_profile = {0};
+    target_profile.save = 4;
+    AosTarget *target = NULL;
+    CHECK(aos_target_new(&target_profile, &target) == AOS_STATUS_OK);
+
+    double damage = 0.0;
+    CHECK(aos_average_damage(weapon, target, &damage) == AOS_STATUS_OK);
+    CHECK(close_to(damage, 4.0 / 9.0));
+
+    /* The same target, from JSON */
+    AosTarget *json_target = NULL;
+    CHECK(aos_target_from_json("{\"save\": {\"value\": 4}}", &json_target) == AOS_STATUS_OK);
+    double json_damage = 0.0;
+    CHECK(aos_average_damage(weapon, json_target, &json_damage) == AOS_STATUS_OK);
+    CHECK(close_to(damage, json_damage));
+
+    /* Query the length of the distribution, then fetch it */
+    size_t len = 0;
+    CHECK(aos_damage_distribution(weapon, target, NULL, 0, &len) == AOS_STATUS_BUFFER_TOO_SMALL);
+    CHECK(len == 3);
+    double probabilities[3];
+    CHECK(aos_damage_distribution(weapon, target, probabilities, 3, &len) == AOS_STATUS_OK);
+    double mean = 0.0, total = 0.0;
+    for (size_t k = 0; k < len; k++) {
+        total += probabilities[k];
+        mean += (double)k * probabilities[k];
+    }
+    CHECK(close_to(total, 1.0));
+    CHECK(close_to(mean, damage));
+
+    AosSummary summary = {0};
+    CHECK(aos_damage_summary(weapon, target, &summary) == AOS_STATUS_OK);
+    CHECK(close_to(summary.mean, damage));
+    CHECK(summary.p10 == 0 && summary.p50 == 0 && summary.p90 == 1);
+    CHECK(close_to(summary.zero, probabilities[0]));
+
+    double table[AOS_SAVE_TABLE_LEN];
+    CHECK(aos_save_table(weapon, NULL, table) == AOS_STATUS_OK);
+    CHECK(close_to(table[2], damage));
+    CHECK(table[0] < table[AOS_SAVE_TABLE_LEN - 1]);
+    AosSummary summaries[AOS_SAVE_TABLE_LEN];
+    CHECK(aos_save_table_summary(weapon, NULL, summaries) == AOS_STATUS_OK);
+    CHECK(close_to(summaries[2].mean, table[2]));
+    /* Worse saves let more damage through */
+    CHECK(summaries[AOS_SAVE_TABLE_LEN - 1].zero < summaries[0].zero);
+
+    /* Errors come back as a status and a message */
+    AosWeapon *invalid = NULL;
+    CHECK(aos_weapon_from_json("{\"attacks\": 2}", &invalid) == AOS_STATUS_INVALID_JSON);
+    CHECK(invalid == NULL);
+    CHECK(aos_last_error_message() != NULL);
+    profile.hit.reroll = 42;
+    CHECK(aos_weapon_new(&profile, &invalid) == AOS_STATUS_INVALID_PROFILE);
+    CHECK(strcmp(aos_last_error_message(), "unknown reroll type 42") == 0);
+    CHECK(aos_average_damage(NULL, target, &damage) == AOS_STATUS_NULL_POINTER);
+
+    aos_weapon_free(weapon);
+    aos_target_free(target);
+    aos_target_free(json_target);
+    aos_weapon_free(NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("All checks passed\n");
+    return 0;
+}
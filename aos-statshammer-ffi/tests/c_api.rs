@@ -0,0 +1,65 @@
+//! Compiles `tests/c/test_ffi.c` against the generated header and the shared
+//! library, then runs it.
+//!
+//! Cargo only builds the `rlib` for integration tests, so the shared library
+//! is built here first.
+//!
+//! The committed header is also checked against the one generated by the build
+//! script, so that it can't fall behind the code.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn c_program() {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // Integration tests live in `target/<profile>/deps`, next to the library
+    let lib_dir = env::current_exe()
+        .unwrap()
+        .parent()
+        .and_then(|deps| deps.parent())
+        .unwrap()
+        .to_path_buf();
+    let exe = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("test_ffi");
+
+    let mut cargo = Command::new(env!("CARGO"));
+    cargo.args(["build", "--lib", "--package", env!("CARGO_PKG_NAME")]);
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    let status = cargo.status().expect("Unable to run cargo");
+    assert!(status.success(), "Unable to build the shared library");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .arg(crate_dir.join("tests/c/test_ffi.c"))
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .args(["-laos_statshammer_ffi", "-lm", "-Wall", "-Werror", "-o"])
+        .arg(&exe)
+        .status()
+        .expect("Unable to run the C compiler");
+    assert!(status.success(), "Unable to compile the C test program");
+
+    let output = Command::new(&exe).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/aos_statshammer.h"));
+    let committed = include_str!("../include/aos_statshammer.h");
+    assert!(
+        generated == committed,
+        "include/aos_statshammer.h is out of date, run \
+         `AOS_STATSHAMMER_UPDATE_HEADER=1 cargo build -p aos-statshammer-ffi` to update it"
+    );
+}
@@ -0,0 +1,101 @@
+from typing import Optional, TypedDict
+
+class RerollType:
+    Ones: RerollType
+    Failed: RerollType
+    Any: RerollType
+
+class Summary(TypedDict):
+    mean: float
+    std_dev: float
+    coefficient_of_variation: Optional[float]
+    p10: int
+    p50: int
+    p90: int
+    zero: float
+
+class ExplodingAbility:
+    def __init__(self, on: int = 6, unmodified: bool = True, extra: int = 1) -> None: ...
+    @property
+    def on(self) -> int: ...
+    @property
+    def unmodified(self) -> bool: ...
+    @property
+    def extra(self) -> int: ...
+
+class Target:
+    def __init__(self, save: int, ward: Optional[int] = None, ethereal: bool = False) -> None: ...
+    @property
+    def save(self) -> int: ...
+    @property
+    def modified_save(self) -> int: ...
+    @property
+    def ward(self) -> Optional[int]: ...
+    @property
+    def ethereal(self) -> bool: ...
+
+class TargetBuilder:
+    def __init__(self) -> None: ...
+    def save(
+        self, value: int, bonus: int = 0, reroll: Optional[RerollType] = None
+    ) -> TargetBuilder: ...
+    def ward(self, value: int) -> TargetBuilder: ...
+    def ethereal(self, value: bool = True) -> TargetBuilder: ...
+    def build(self) -> Target: ...
+
+class Weapon:
+    def __init__(
+        self, attacks: int, hit: int, wound: int, rend: int = 0, damage: int = 1
+    ) -> None: ...
+    @property
+    def attacks(self) -> int: ...
+    @property
+    def hit(self) -> int: ...
+    @property
+    def wound(self) -> int: ...
+    @property
+    def rend(self) -> int: ...
+    @property
+    def damage(self) -> int: ...
+    @property
+    def modified_attacks(self) -> int: ...
+    @property
+    def modified_hit(self) -> int: ...
+    @property
+    def modified_wound(self) -> int: ...
+    @property
+    def modified_rend(self) -> int: ...
+    @property
+    def modified_damage(self) -> int: ...
+    def average_damage(self, target: Target) -> float: ...
+    def damage_distribution(self, target: Target) -> list[float]: ...
+    def damage_summary(self, target: Target) -> Summary: ...
+    def save_table(self, target: Optional[Target] = None) -> list[tuple[str, float]]: ...
+    def simulate_damage(
+        self, target: Target, trials: int = 10000, seed: int = 0
+    ) -> list[float]: ...
+
+class WeaponBuilder:
+    def __init__(self) -> None: ...
+    def attacks(self, value: int, bonus: int = 0) -> WeaponBuilder: ...
+    def hit(
+        self,
+        value: int,
+        bonus: int = 0,
+        reroll: Optional[RerollType] = None,
+        exploding: Optional[ExplodingAbility] = None,
+    ) -> WeaponBuilder: ...
+    def wound(
+        self,
+        value: int,
+        bonus: int = 0,
+        reroll: Optional[RerollType] = None,
+        exploding: Optional[ExplodingAbility] = None,
+    ) -> WeaponBuilder: ...
+    def rend(self, value: int, bonus: int = 0) -> WeaponBuilder: ...
+    def damage(self, value: int, bonus: int = 0) -> WeaponBuilder: ...
+    def build(self) -> Weapon: ...
+
+def average_damage_matrix(weapons: list[Weapon], targets: list[Target]) -> list[list[float]]: ...
+def damage_distributions(weapons: list[Weapon], target: Target) -> list[list[float]]: ...
+def damage_summaries(weapons: list[Weapon], target: Target) -> list[Summary]: ...
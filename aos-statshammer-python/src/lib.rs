@@ -0,0 +1,526 @@
+//! Python bindings for the core calculations.
+//!
+//! Every result is returned as plain Python floats, lists, tuples and dicts so
+//! that it can be handed straight to pandas/numpy in a notebook.
+
+use aos_statshammer_core::distribution;
+use aos_statshammer_core::target::{self, Save, TargetBuilder as CoreTargetBuilder};
+use aos_statshammer_core::weapon::{self, WeaponBuilder as CoreWeaponBuilder};
+use aos_statshammer_core::{Characteristic, RerollType as CoreRerollType, validate};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+#[pyclass(eq, eq_int, frozen, from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RerollType {
+    Ones,
+    Failed,
+    Any,
+}
+
+impl From<RerollType> for CoreRerollType {
+    fn from(value: RerollType) -> Self {
+        match value {
+            RerollType::Ones => CoreRerollType::Ones,
+            RerollType::Failed => CoreRerollType::Failed,
+            RerollType::Any => CoreRerollType::Any,
+        }
+    }
+}
+
+impl From<CoreRerollType> for RerollType {
+    fn from(value: CoreRerollType) -> Self {
+        match value {
+            CoreRerollType::Ones => RerollType::Ones,
+            CoreRerollType::Failed => RerollType::Failed,
+            CoreRerollType::Any => RerollType::Any,
+        }
+    }
+}
+
+/// Extra rolls (e.g. "Unmodified 6s to hit score 2 hits").
+#[pyclass(frozen, from_py_object)]
+#[derive(Debug, Clone, Copy)]
+pub struct ExplodingAbility(weapon::ExplodingAbility);
+
+#[pymethods]
+impl ExplodingAbility {
+    #[new]
+    #[pyo3(signature = (on = 6, unmodified = true, extra = 1))]
+    fn new(on: u8, unmodified: bool, extra: u8) -> Self {
+        Self(weapon::ExplodingAbility {
+            on,
+            unmodified,
+            extra,
+        })
+    }
+
+    #[getter]
+    fn on(&self) -> u8 {
+        self.0.on
+    }
+
+    #[getter]
+    fn unmodified(&self) -> bool {
+        self.0.unmodified
+    }
+
+    #[getter]
+    fn extra(&self) -> u8 {
+        self.0.extra
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ExplodingAbility(on={}, unmodified={}, extra={})",
+            self.0.on,
+            py_bool(self.0.unmodified),
+            self.0.extra
+        )
+    }
+}
+
+/// A weapon profile. Use `WeaponBuilder` to add modifiers and abilities.
+#[pyclass(frozen, from_py_object)]
+#[derive(Debug, Clone)]
+pub struct Weapon(weapon::Weapon);
+
+#[pymethods]
+impl Weapon {
+    #[new]
+    #[pyo3(signature = (attacks, hit, wound, rend = 0, damage = 1))]
+    fn new(attacks: u8, hit: u8, wound: u8, rend: u8, damage: u8) -> PyResult<Self> {
+        CoreWeaponBuilder::default()
+            .attacks(attacks)
+            .hit(hit)
+            .wound(wound)
+            .rend(rend)
+            .damage(damage)
+            .build()
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+            .and_then(valid_weapon)
+            .map(Self)
+    }
+
+    #[getter]
+    fn attacks(&self) -> u8 {
+        self.0.attacks.unmodified()
+    }
+
+    #[getter]
+    fn hit(&self) -> u8 {
+        self.0.hit.unmodified()
+    }
+
+    #[getter]
+    fn wound(&self) -> u8 {
+        self.0.wound.unmodified()
+    }
+
+    #[getter]
+    fn rend(&self) -> u8 {
+        self.0.rend.unmodified()
+    }
+
+    #[getter]
+    fn damage(&self) -> u8 {
+        self.0.damage.unmodified()
+    }
+
+    /// The attacks characteristic, after any bonus.
+    #[getter]
+    fn modified_attacks(&self) -> u8 {
+        self.0.attacks.modified()
+    }
+
+    /// The hit characteristic, after any bonus.
+    #[getter]
+    fn modified_hit(&self) -> u8 {
+        self.0.hit.modified()
+    }
+
+    /// The wound characteristic, after any bonus.
+    #[getter]
+    fn modified_wound(&self) -> u8 {
+        self.0.wound.modified()
+    }
+
+    /// The rend characteristic, after any bonus.
+    #[getter]
+    fn modified_rend(&self) -> u8 {
+        self.0.rend.modified()
+    }
+
+    /// The damage characteristic, after any bonus.
+    #[getter]
+    fn modified_damage(&self) -> u8 {
+        self.0.damage.modified()
+    }
+
+    fn average_damage(&self, target: &Target) -> f64 {
+        self.0.average_damage(&target.0)
+    }
+
+    /// The exact damage distribution, where item `k` is `P(damage = k)`.
+    fn damage_distribution(&self, target: &Target) -> Vec<f64> {
+        self.0.damage_distribution(&target.0).pmf().to_vec()
+    }
+
+    /// The statistics which describe the damage distribution, as a dict.
+    fn damage_summary(&self, py: Python<'_>, target: &Target) -> Summary {
+        py.detach(|| self.0.damage_distribution(&target.0).summary().into())
+    }
+
+    /// The average damage against every save from 2+ down to no save, as
+    /// `(save, average_damage)` pairs. Only the modifiers of `target` are used.
+    #[pyo3(signature = (target = None))]
+    fn save_table(&self, target: Option<&Target>) -> Vec<(String, f64)> {
+        let target = target.map_or_else(|| target::Target::from(Save::NONE), |t| t.0.clone());
+        self.0
+            .save_table(&target)
+            .into_iter()
+            .map(|(save, damage)| (save.to_string(), damage))
+            .collect()
+    }
+
+    /// Estimate the damage distribution by rolling dice, where item `k` is the
+    /// fraction of trials which dealt `k` damage.
+    #[pyo3(signature = (target, trials = 10_000, seed = 0))]
+    fn simulate_damage(
+        &self,
+        py: Python<'_>,
+        target: &Target,
+        trials: usize,
+        seed: u64,
+    ) -> Vec<f64> {
+        py.detach(|| self.0.simulate_damage(&target.0, trials, seed))
+            .pmf()
+            .to_vec()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Weapon(attacks={}, hit={}, wound={}, rend={}, damage={})",
+            self.attacks(),
+            self.hit(),
+            self.wound(),
+            self.rend(),
+            self.damage()
+        )
+    }
+}
+
+/// Builds a `Weapon`, with any modifiers and abilities.
+#[pyclass(from_py_object)]
+#[derive(Debug, Clone, Default)]
+pub struct WeaponBuilder {
+    attacks: Option<weapon::Attacks>,
+    hit: Option<weapon::Hit>,
+    wound: Option<weapon::Wound>,
+    rend: Option<weapon::Rend>,
+    damage: Option<weapon::Damage>,
+}
+
+#[pymethods]
+impl WeaponBuilder {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    #[pyo3(signature = (value, bonus = 0))]
+    fn attacks(mut slf: PyRefMut<'_, Self>, value: u8, bonus: i16) -> PyRefMut<'_, Self> {
+        slf.attacks = Some(weapon::Attacks { value, bonus });
+        slf
+    }
+
+    #[pyo3(signature = (value, bonus = 0, reroll = None, exploding = None))]
+    fn hit(
+        mut slf: PyRefMut<'_, Self>,
+        value: u8,
+        bonus: i16,
+        reroll: Option<RerollType>,
+        exploding: Option<ExplodingAbility>,
+    ) -> PyRefMut<'_, Self> {
+        slf.hit = Some(weapon::Hit {
+            value,
+            bonus,
+            reroll: reroll.map(Into::into),
+            exploding: exploding.map(|ab| ab.0),
+        });
+        slf
+    }
+
+    #[pyo3(signature = (value, bonus = 0, reroll = None, exploding = None))]
+    fn wound(
+        mut slf: PyRefMut<'_, Self>,
+        value: u8,
+        bonus: i16,
+        reroll: Option<RerollType>,
+        exploding: Option<ExplodingAbility>,
+    ) -> PyRefMut<'_, Self> {
+        slf.wound = Some(weapon::Wound {
+            value,
+            bonus,
+            reroll: reroll.map(Into::into),
+            exploding: exploding.map(|ab| ab.0),
+        });
+        slf
+    }
+
+    #[pyo3(signature = (value, bonus = 0))]
+    fn rend(mut slf: PyRefMut<'_, Self>, value: u8, bonus: i16) -> PyRefMut<'_, Self> {
+        slf.rend = Some(weapon::Rend { value, bonus });
+        slf
+    }
+
+    #[pyo3(signature = (value, bonus = 0))]
+    fn damage(mut slf: PyRefMut<'_, Self>, value: u8, bonus: i16) -> PyRefMut<'_, Self> {
+        slf.damage = Some(weapon::Damage { value, bonus });
+        slf
+    }
+
+    fn build(&self) -> PyResult<Weapon> {
+        let mut builder = CoreWeaponBuilder::default();
+        if let Some(attacks) = self.attacks {
+            builder.attacks(attacks);
+        }
+        if let Some(hit) = self.hit {
+            builder.hit(hit);
+        }
+        if let Some(wound) = self.wound {
+            builder.wound(wound);
+        }
+        if let Some(rend) = self.rend {
+            builder.rend(rend);
+        }
+        if let Some(damage) = self.damage {
+            builder.damage(damage);
+        }
+        builder
+            .build()
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+            .and_then(valid_weapon)
+            .map(Weapon)
+    }
+}
+
+/// The target of an attack. Use `TargetBuilder` to add save modifiers.
+#[pyclass(frozen, from_py_object)]
+#[derive(Debug, Clone)]
+pub struct Target(target::Target);
+
+#[pymethods]
+impl Target {
+    #[new]
+    #[pyo3(signature = (save, ward = None, ethereal = false))]
+    fn new(save: u8, ward: Option<u8>, ethereal: bool) -> PyResult<Self> {
+        let mut builder = CoreTargetBuilder::default();
+        builder.save(save).ethereal(ethereal);
+        if let Some(ward) = ward {
+            builder.ward(ward);
+        }
+        builder
+            .build()
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+            .and_then(valid_target)
+            .map(Self)
+    }
+
+    #[getter]
+    fn save(&self) -> u8 {
+        self.0.save.unmodified()
+    }
+
+    /// The save characteristic, after any bonus.
+    #[getter]
+    fn modified_save(&self) -> u8 {
+        self.0.save.modified()
+    }
+
+    #[getter]
+    fn ward(&self) -> Option<u8> {
+        self.0.ward
+    }
+
+    #[getter]
+    fn ethereal(&self) -> bool {
+        self.0.ethereal
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Target(save={}, ward={}, ethereal={})",
+            self.save(),
+            self.ward().map_or("None".to_string(), |w| w.to_string()),
+            py_bool(self.ethereal())
+        )
+    }
+}
+
+/// Builds a `Target`, with any save modifiers.
+#[pyclass(from_py_object)]
+#[derive(Debug, Clone, Default)]
+pub struct TargetBuilder {
+    save: Option<Save>,
+    ward: Option<u8>,
+    ethereal: bool,
+}
+
+#[pymethods]
+impl TargetBuilder {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    #[pyo3(signature = (value, bonus = 0, reroll = None))]
+    fn save(
+        mut slf: PyRefMut<'_, Self>,
+        value: u8,
+        bonus: i16,
+        reroll: Option<RerollType>,
+    ) -> PyRefMut<'_, Self> {
+        slf.save = Some(Save {
+            value,
+            bonus,
+            reroll: reroll.map(Into::into),
+        });
+        slf
+    }
+
+    fn ward(mut slf: PyRefMut<'_, Self>, value: u8) -> PyRefMut<'_, Self> {
+        slf.ward = Some(value);
+        slf
+    }
+
+    #[pyo3(signature = (value = true))]
+    fn ethereal(mut slf: PyRefMut<'_, Self>, value: bool) -> PyRefMut<'_, Self> {
+        slf.ethereal = value;
+        slf
+    }
+
+    fn build(&self) -> PyResult<Target> {
+        let mut builder = CoreTargetBuilder::default();
+        if let Some(save) = self.save {
+            builder.save(save);
+        }
+        if let Some(ward) = self.ward {
+            builder.ward(ward);
+        }
+        builder.ethereal(self.ethereal);
+        builder
+            .build()
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+            .and_then(valid_target)
+            .map(Target)
+    }
+}
+
+fn valid_weapon(weapon: weapon::Weapon) -> PyResult<weapon::Weapon> {
+    valid(validate::weapon(&weapon)).map(|()| weapon)
+}
+
+fn valid_target(target: target::Target) -> PyResult<target::Target> {
+    valid(validate::target(&target)).map(|()| target)
+}
+
+/// A `ValueError` listing every problem (using the names of the core wire
+/// format), if there are any.
+fn valid(problems: Vec<String>) -> PyResult<()> {
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "Invalid profile: {}",
+            problems.join("; ")
+        )))
+    }
+}
+
+/// The average damage of every weapon against every target, as a list of
+/// rows (one per weapon) with a column for each target.
+#[pyfunction]
+fn average_damage_matrix(
+    py: Python<'_>,
+    weapons: Vec<Weapon>,
+    targets: Vec<Target>,
+) -> Vec<Vec<f64>> {
+    py.detach(|| {
+        weapons
+            .iter()
+            .map(|weapon| {
+                targets
+                    .iter()
+                    .map(|target| weapon.0.average_damage(&target.0))
+                    .collect()
+            })
+            .collect()
+    })
+}
+
+/// The exact damage distribution of every weapon against `target`.
+#[pyfunction]
+fn damage_distributions(py: Python<'_>, weapons: Vec<Weapon>, target: Target) -> Vec<Vec<f64>> {
+    py.detach(|| {
+        weapons
+            .iter()
+            .map(|weapon| weapon.0.damage_distribution(&target.0).pmf().to_vec())
+            .collect()
+    })
+}
+
+/// The statistics which describe the damage dealt by every weapon to `target`.
+#[pyfunction]
+fn damage_summaries(py: Python<'_>, weapons: Vec<Weapon>, target: Target) -> Vec<Summary> {
+    py.detach(|| {
+        weapons
+            .iter()
+            .map(|weapon| weapon.0.damage_distribution(&target.0).summary().into())
+            .collect()
+    })
+}
+
+/// [`distribution::Summary`], which is converted to a dict.
+#[derive(IntoPyObject)]
+struct Summary {
+    mean: f64,
+    std_dev: f64,
+    coefficient_of_variation: Option<f64>,
+    p10: usize,
+    p50: usize,
+    p90: usize,
+    zero: f64,
+}
+
+impl From<distribution::Summary> for Summary {
+    fn from(summary: distribution::Summary) -> Self {
+        Self {
+            mean: summary.mean,
+            std_dev: summary.std_dev,
+            coefficient_of_variation: summary.coefficient_of_variation,
+            p10: summary.p10,
+            p50: summary.p50,
+            p90: summary.p90,
+            zero: summary.zero,
+        }
+    }
+}
+
+fn py_bool(value: bool) -> &'static str {
+    if value { "True" } else { "False" }
+}
+
+#[pymodule]
+fn aos_statshammer(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<RerollType>()?;
+    m.add_class::<ExplodingAbility>()?;
+    m.add_class::<Weapon>()?;
+    m.add_class::<WeaponBuilder>()?;
+    m.add_class::<Target>()?;
+    m.add_class::<TargetBuilder>()?;
+    m.add_function(wrap_pyfunction!(average_damage_matrix, m)?)?;
+    m.add_function(wrap_pyfunction!(damage_distributions, m)?)?;
+    m.add_function(wrap_pyfunction!(damage_summaries, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,116 @@
+import math
+
+import pytest
+
+from aos_statshammer import (
+    ExplodingAbility,
+    RerollType,
+    Target,
+    TargetBuilder,
+    Weapon,
+    WeaponBuilder,
+    average_damage_matrix,
+    damage_distributions,
+    damage_summaries,
+)
+
+
+def weapon():
+    return Weapon(attacks=2, hit=3, wound=4, rend=1, damage=1)
+
+
+def test_average_damage():
+    # 2 attacks * 2/3 hit * 1/2 wound * 2/3 unsaved (4+ save with rend 1)
+    assert weapon().average_damage(Target(4)) == pytest.approx(4 / 9)
+
+
+def test_weapon_properties():
+    w = weapon()
+    assert (w.attacks, w.hit, w.wound, w.rend, w.damage) == (2, 3, 4, 1, 1)
+
+
+def test_builder_with_abilities():
+    w = (
+        WeaponBuilder()
+        .attacks(2, bonus=1)
+        .hit(3, reroll=RerollType.Ones, exploding=ExplodingAbility())
+        .wound(4)
+        .rend(1)
+        .damage(2)
+        .build()
+    )
+    assert (w.attacks, w.modified_attacks) == (2, 3)
+    assert w.average_damage(Target(4)) > Weapon(3, 3, 4, 1, 2).average_damage(Target(4))
+
+
+def test_builder_missing_characteristic():
+    with pytest.raises(ValueError, match="hit"):
+        WeaponBuilder().attacks(2).build()
+
+
+def test_values_out_of_range():
+    with pytest.raises(ValueError, match=r"weapon\.hit\.value must be between 1 and 6 \(got 0\)"):
+        Weapon(attacks=2, hit=0, wound=4)
+    with pytest.raises(ValueError, match=r"weapon\.wound\.value"):
+        WeaponBuilder().attacks(2).hit(3).wound(7).rend(0).damage(1).build()
+    with pytest.raises(ValueError, match=r"target\.save\.value"):
+        Target(0)
+    with pytest.raises(ValueError, match=r"target\.ward must be between 2 and 6 \(got 7\)"):
+        Target(4, ward=7)
+    with pytest.raises(ValueError, match=r"target\.ward"):
+        TargetBuilder().save(4).ward(1).build()
+
+
+def test_target_builder():
+    target = TargetBuilder().save(4, bonus=1, reroll=RerollType.Failed).ward(5).ethereal().build()
+    assert (target.save, target.modified_save) == (4, 3)
+    assert target.ward == 5
+    assert target.ethereal
+    assert Target(4, ward=5).ward == 5
+
+
+def test_damage_distribution():
+    dist = weapon().damage_distribution(Target(4))
+    assert isinstance(dist, list)
+    assert len(dist) == 3
+    assert sum(dist) == pytest.approx(1.0)
+    assert sum(k * p for k, p in enumerate(dist)) == pytest.approx(4 / 9)
+
+
+def test_damage_summary():
+    summary = weapon().damage_summary(Target(4))
+    assert summary["mean"] == pytest.approx(4 / 9)
+    assert (summary["p10"], summary["p50"], summary["p90"]) == (0, 0, 1)
+    assert summary["zero"] == pytest.approx((7 / 9) ** 2)
+    assert damage_summaries([weapon()], Target(4)) == [summary]
+
+
+def test_save_table():
+    table = weapon().save_table()
+    assert [save for save, _ in table] == ["2+", "3+", "4+", "5+", "6+", "-"]
+    assert table[2][1] == pytest.approx(4 / 9)
+
+
+def test_simulate_damage_is_repeatable():
+    first = weapon().simulate_damage(Target(4), trials=1000, seed=7)
+    assert first == weapon().simulate_damage(Target(4), trials=1000, seed=7)
+    assert sum(first) == pytest.approx(1.0)
+
+
+def test_bulk_evaluation():
+    weapons = [weapon(), Weapon(4, 4, 4)]
+    targets = [Target(3), Target(4), Target(7)]
+    matrix = average_damage_matrix(weapons, targets)
+    assert len(matrix) == 2
+    assert all(len(row) == 3 for row in matrix)
+    assert matrix[0][1] == pytest.approx(4 / 9)
+    assert all(isinstance(value, float) for row in matrix for value in row)
+
+    dists = damage_distributions(weapons, Target(4))
+    assert len(dists) == 2
+    assert all(math.isclose(sum(dist), 1.0) for dist in dists)
+
+
+def test_reroll_type_equality():
+    assert RerollType.Ones == RerollType.Ones
+    assert RerollType.Ones != RerollType.Any
@@ -0,0 +1,12 @@
+//! A local REST API exposing the core calculations as JSON over HTTP.
+//!
+//! Every calculation endpoint takes a `POST` with a JSON body containing the
+//! weapon and target (in the wire format of the core crate's `serde` feature).
+//! The OpenAPI document describing the API is served at `/openapi.json`.
+
+mod error;
+mod openapi;
+mod routes;
+mod validate;
+
+pub use routes::router;
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use clap::Parser;
+use log::{LevelFilter, info};
+use simple_logger::SimpleLogger;
+use std::net::IpAddr;
+
+#[derive(Parser, Debug)]
+struct Cli {
+    /// The address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: IpAddr,
+
+    /// The port to listen on
+    #[arg(short, long, default_value_t = 3000)]
+    port: u16,
+
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    SimpleLogger::new().init()?;
+    if cli.verbose {
+        log::set_max_level(LevelFilter::Debug);
+    } else {
+        log::set_max_level(LevelFilter::Info);
+    }
+
+    let listener = tokio::net::TcpListener::bind((cli.host, cli.port)).await?;
+    info!("Listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, aos_statshammer_server::router()).await?;
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use crate::routes::MAX_TRIALS;
+use aos_statshammer_core::weapon::MAX_SUMMARY_DAMAGE;
+use serde_json::{Value, json};
+
+/// The OpenAPI (3.1) document describing every endpoint.
+pub(crate) fn document() -> Value {
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "AOS Statshammer",
+            "description": "Damage calculations for Age of Sigmar weapon profiles",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": {
+            "/average": calculation(
+                "The average damage of a weapon against a target",
+                "ProfileRequest",
+                "AverageResponse",
+            ),
+            "/distribution": calculation(
+                "The exact distribution of the damage dealt by a weapon to a target",
+                "ProfileRequest",
+                "DistributionReport",
+            ),
+            "/save-table": calculation(
+                "The average damage of a weapon against every save characteristic",
+                "SaveTableRequest",
+                "SaveTableResponse",
+            ),
+            "/simulate": calculation(
+                "Estimate the damage distribution by rolling dice",
+                "SimulateRequest",
+                "SimulateResponse",
+            ),
+            "/openapi.json": {
+                "get": {
+                    "summary": "This document",
+                    "responses": {
+                        "200": {
+                            "description": "The OpenAPI document",
+                            "content": { "application/json": { "schema": { "type": "object" } } },
+                        },
+                    },
+                },
+            },
+        },
+        "components": { "schemas": schemas() },
+    })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+/// A summary which is left out (as null) for weapons which can do too much damage.
+fn optional_summary() -> Value {
+    json!({
+        "oneOf": [schema_ref("Summary"), { "type": "null" }],
+        "description": format!(
+            "Null if the weapon can do more than {MAX_SUMMARY_DAMAGE} damage, as the distribution would take too long to calculate"
+        ),
+    })
+}
+
+fn calculation(summary: &str, request: &str, response: &str) -> Value {
+    let error = json!({ "application/json": { "schema": schema_ref("Error") } });
+    json!({
+        "post": {
+            "summary": summary,
+            "requestBody": {
+                "required": true,
+                "content": { "application/json": { "schema": schema_ref(request) } },
+            },
+            "responses": {
+                "200": {
+                    "description": "The result of the calculation",
+                    "content": { "application/json": { "schema": schema_ref(response) } },
+                },
+                "400": { "description": "The request body is not valid JSON", "content": error },
+                "415": { "description": "The request is missing a JSON content type", "content": error },
+                "422": {
+                    "description": "The request body is missing fields or has values out of range",
+                    "content": error,
+                },
+                "500": { "description": "The calculation failed unexpectedly", "content": error },
+            },
+        },
+    })
+}
+
+fn schemas() -> Value {
+    let probabilities = json!({
+        "type": "array",
+        "items": { "type": "number" },
+    });
+    json!({
+        "Characteristic": {
+            "type": "object",
+            "required": ["value"],
+            "properties": {
+                "value": { "type": "integer", "minimum": 0 },
+                "bonus": { "type": "integer", "default": 0 },
+            },
+        },
+        "RollCharacteristic": {
+            "type": "object",
+            "required": ["value"],
+            "properties": {
+                "value": { "type": "integer", "minimum": 1, "maximum": 6 },
+                "bonus": { "type": "integer", "default": 0 },
+                "reroll": schema_ref("RerollType"),
+                "exploding": schema_ref("ExplodingAbility"),
+            },
+        },
+        "RerollType": { "type": "string", "enum": ["ones", "failed", "any"] },
+        "ExplodingAbility": {
+            "type": "object",
+            "properties": {
+                "on": { "type": "integer", "minimum": 1, "maximum": 6, "default": 6 },
+                "unmodified": { "type": "boolean", "default": true },
+                "extra": { "type": "integer", "minimum": 0, "default": 1 },
+            },
+        },
+        "Weapon": {
+            "type": "object",
+            "required": ["attacks", "hit", "wound", "rend", "damage"],
+            "properties": {
+                "attacks": schema_ref("Characteristic"),
+                "hit": schema_ref("RollCharacteristic"),
+                "wound": schema_ref("RollCharacteristic"),
+                "rend": schema_ref("Characteristic"),
+                "damage": schema_ref("Characteristic"),
+            },
+        },
+        "Save": {
+            "type": "object",
+            "required": ["value"],
+            "properties": {
+                "value": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": 7,
+                    "description": "The save characteristic (7 for no save)",
+                },
+                "bonus": { "type": "integer", "default": 0 },
+                "reroll": schema_ref("RerollType"),
+            },
+        },
+        "Target": {
+            "type": "object",
+            "required": ["save"],
+            "properties": {
+                "save": schema_ref("Save"),
+                "ethereal": { "type": "boolean", "default": false },
+                "ward": { "type": "integer", "minimum": 2, "maximum": 6 },
+                "health": { "type": "integer", "minimum": 1, "default": 1 },
+                "models": { "type": "integer", "minimum": 1, "default": 1 },
+            },
+        },
+        "ProfileRequest": {
+            "type": "object",
+            "required": ["weapon", "target"],
+            "properties": {
+                "weapon": schema_ref("Weapon"),
+                "target": schema_ref("Target"),
+            },
+        },
+        "SaveTableRequest": {
+            "type": "object",
+            "required": ["weapon"],
+            "properties": {
+                "weapon": schema_ref("Weapon"),
+                "target": {
+                    "$ref": "#/components/schemas/Target",
+                    "description": "Only the target's modifiers are used, as the save is replaced in every row",
+                },
+            },
+        },
+        "SimulateRequest": {
+            "type": "object",
+            "required": ["weapon", "target"],
+            "properties": {
+                "weapon": schema_ref("Weapon"),
+                "target": schema_ref("Target"),
+                "trials": { "type": "integer", "minimum": 1, "maximum": MAX_TRIALS, "default": 10000 },
+                "seed": { "type": "integer", "minimum": 0, "description": "Defaults to a random seed" },
+            },
+        },
+        "AverageResponse": {
+            "type": "object",
+            "required": ["average_damage", "summary"],
+            "properties": {
+                "average_damage": { "type": "number" },
+                "summary": optional_summary(),
+            },
+        },
+        "DistributionReport": {
+            "type": "object",
+            "required": ["summary", "probability", "at_least"],
+            "properties": {
+                "summary": schema_ref("Summary"),
+                "probability": probabilities.clone(),
+                "at_least": probabilities,
+            },
+        },
+        "Summary": {
+            "type": "object",
+            "required": ["mean", "std_dev", "coefficient_of_variation", "p10", "p50", "p90", "zero"],
+            "properties": {
+                "mean": { "type": "number" },
+                "std_dev": { "type": "number" },
+                "coefficient_of_variation": {
+                    "type": ["number", "null"],
+                    "description": "The standard deviation relative to the mean (null if the mean is zero)",
+                },
+                "p10": { "type": "integer" },
+                "p50": { "type": "integer" },
+                "p90": { "type": "integer" },
+                "zero": { "type": "number", "description": "The probability of no damage" },
+            },
+        },
+        "SaveTableResponse": {
+            "type": "object",
+            "required": ["rows"],
+            "properties": {
+                "rows": {
+                    "type": "array",
+                    "items": schema_ref("SaveTableRow"),
+                },
+            },
+        },
+        "SaveTableRow": {
+            "type": "object",
+            "required": ["save", "average_damage", "summary"],
+            "properties": {
+                "save": { "type": "string", "examples": ["4+", "-"] },
+                "average_damage": { "type": "number" },
+                "summary": optional_summary(),
+            },
+        },
+        "SimulateResponse": {
+            "type": "object",
+            "required": ["trials", "seed", "exact_mean", "simulated"],
+            "properties": {
+                "trials": { "type": "integer" },
+                "seed": { "type": "integer" },
+                "exact_mean": { "type": "number" },
+                "simulated": schema_ref("DistributionReport"),
+            },
+        },
+        "Error": {
+            "type": "object",
+            "required": ["error"],
+            "properties": {
+                "error": { "type": "string" },
+                "details": { "type": "array", "items": { "type": "string" } },
+            },
+        },
+    })
+}
@@ -0,0 +1,163 @@
+use crate::error::ApiError;
+use crate::openapi;
+use crate::validate::{Valid, Validate};
+use aos_statshammer_core::distribution::Summary;
+use aos_statshammer_core::report::{DistributionReport, SaveTableRow};
+use aos_statshammer_core::target::{Save, Target};
+use aos_statshammer_core::validate;
+use aos_statshammer_core::weapon::Weapon;
+use axum::Json;
+use axum::Router;
+use axum::routing::{get, post};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The most trials a single simulation request may ask for.
+pub(crate) const MAX_TRIALS: u32 = 1_000_000;
+
+/// The routes for every endpoint in the API.
+pub fn router() -> Router {
+    Router::new()
+        .route("/average", post(average))
+        .route("/distribution", post(distribution))
+        .route("/save-table", post(save_table))
+        .route("/simulate", post(simulate))
+        .route("/openapi.json", get(openapi_document))
+}
+
+#[derive(Deserialize, Debug)]
+struct ProfileRequest {
+    weapon: Weapon,
+    target: Target,
+}
+
+impl Validate for ProfileRequest {
+    fn problems(&self) -> Vec<String> {
+        let mut problems = validate::weapon(&self.weapon);
+        problems.extend(validate::target(&self.target));
+        problems
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SaveTableRequest {
+    weapon: Weapon,
+    /// Only the target's modifiers are used, as the save is replaced in every row
+    #[serde(default)]
+    target: Option<Target>,
+}
+
+impl Validate for SaveTableRequest {
+    fn problems(&self) -> Vec<String> {
+        let mut problems = validate::weapon(&self.weapon);
+        problems.extend(self.target.iter().flat_map(validate::target));
+        problems
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SimulateRequest {
+    weapon: Weapon,
+    target: Target,
+    #[serde(default = "default_trials")]
+    trials: u32,
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+fn default_trials() -> u32 {
+    10_000
+}
+
+impl Validate for SimulateRequest {
+    fn problems(&self) -> Vec<String> {
+        let mut problems = validate::weapon(&self.weapon);
+        problems.extend(validate::target(&self.target));
+        if !(1..=MAX_TRIALS).contains(&self.trials) {
+            problems.push(format!(
+                "trials must be between 1 and {MAX_TRIALS} (got {})",
+                self.trials
+            ));
+        }
+        problems
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct AverageResponse {
+    average_damage: f64,
+    /// `None` if the weapon can do too much damage to calculate it quickly
+    summary: Option<Summary>,
+}
+
+#[derive(Serialize, Debug)]
+struct SaveTableResponse {
+    rows: Vec<SaveTableRow>,
+}
+
+#[derive(Serialize, Debug)]
+struct SimulateResponse {
+    trials: u32,
+    seed: u64,
+    exact_mean: f64,
+    simulated: DistributionReport,
+}
+
+async fn average(Valid(req): Valid<ProfileRequest>) -> Result<Json<AverageResponse>, ApiError> {
+    let response = blocking(move || AverageResponse {
+        average_damage: req.weapon.average_damage(&req.target),
+        summary: req.weapon.damage_summary(&req.target),
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+async fn distribution(
+    Valid(req): Valid<ProfileRequest>,
+) -> Result<Json<DistributionReport>, ApiError> {
+    let report =
+        blocking(move || DistributionReport::from(&req.weapon.damage_distribution(&req.target)))
+            .await?;
+    Ok(Json(report))
+}
+
+async fn save_table(
+    Valid(req): Valid<SaveTableRequest>,
+) -> Result<Json<SaveTableResponse>, ApiError> {
+    let target = req.target.unwrap_or_else(|| Target::from(Save::NONE));
+    let rows = blocking(move || SaveTableRow::for_weapon(&req.weapon, &target)).await?;
+    Ok(Json(SaveTableResponse { rows }))
+}
+
+async fn simulate(Valid(req): Valid<SimulateRequest>) -> Result<Json<SimulateResponse>, ApiError> {
+    let seed = req.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64)
+    });
+    let response = blocking(move || {
+        let simulated = req
+            .weapon
+            .simulate_damage(&req.target, req.trials as usize, seed);
+        SimulateResponse {
+            trials: req.trials,
+            seed,
+            exact_mean: req.weapon.average_damage(&req.target),
+            simulated: DistributionReport::from(&simulated),
+        }
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// Run a calculation on the blocking thread pool, as the larger ones can take long
+/// enough to hold up every other request on the async runtime.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, ApiError> {
+    Ok(tokio::task::spawn_blocking(f).await?)
+}
+
+async fn openapi_document() -> Json<serde_json::Value> {
+    Json(openapi::document())
+}
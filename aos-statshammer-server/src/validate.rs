@@ -0,0 +1,30 @@
+use crate::error::ApiError;
+use axum::Json;
+use axum::extract::{FromRequest, Request};
+use serde::de::DeserializeOwned;
+
+/// A request body which can be checked for out of range values.
+pub(crate) trait Validate {
+    /// A description of every problem with the request (empty if it is valid).
+    fn problems(&self) -> Vec<String>;
+}
+
+/// A JSON request body which has been parsed and validated, rejecting the
+/// request with a 4xx [`ApiError`] otherwise.
+pub(crate) struct Valid<T>(pub T);
+
+impl<S, T> FromRequest<S> for Valid<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        match value.problems() {
+            problems if problems.is_empty() => Ok(Self(value)),
+            problems => Err(ApiError::invalid(problems)),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use aos_statshammer_server::router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+async fn send(request: Request<Body>) -> (StatusCode, Value) {
+    let response = router().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn post(path: &str, body: Value) -> (StatusCode, Value) {
+    let request = Request::post(path)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    send(request).await
+}
+
+fn weapon() -> Value {
+    json!({
+        "attacks": { "value": 2 },
+        "hit": { "value": 3 },
+        "wound": { "value": 4 },
+        "rend": { "value": 1 },
+        "damage": { "value": 1 },
+    })
+}
+
+fn target(save: u8) -> Value {
+    json!({ "save": { "value": save } })
+}
+
+fn assert_close(value: &Value, expected: f64) {
+    let value = value.as_f64().unwrap();
+    assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+}
+
+#[tokio::test]
+async fn average() {
+    let (status, body) = post(
+        "/average",
+        json!({ "weapon": weapon(), "target": target(4) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_close(&body["average_damage"], 4.0 / 9.0);
+    assert_close(&body["summary"]["mean"], 4.0 / 9.0);
+    assert_eq!(body["summary"]["p90"], 1);
+}
+
+#[tokio::test]
+async fn average_has_no_summary_when_the_distribution_is_too_big() {
+    let exploding = json!({ "on": 2, "extra": 20 });
+    let weapon = json!({
+        "attacks": { "value": 255 },
+        "hit": { "value": 3, "exploding": exploding },
+        "wound": { "value": 3, "exploding": exploding },
+        "rend": { "value": 0 },
+        "damage": { "value": 50 },
+    });
+    let (status, body) = post("/average", json!({ "weapon": weapon, "target": target(4) })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["average_damage"].as_f64().unwrap() > 0.0);
+    assert_eq!(body["summary"], Value::Null);
+}
+
+#[tokio::test]
+async fn distribution() {
+    let (status, body) = post(
+        "/distribution",
+        json!({ "weapon": weapon(), "target": target(4) }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_close(&body["summary"]["mean"], 4.0 / 9.0);
+    let probability = body["probability"].as_array().unwrap();
+    assert_eq!(probability.len(), 3);
+    let total: f64 = probability.iter().map(|p| p.as_f64().unwrap()).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert_close(&body["at_least"][0], 1.0);
+}
+
+#[tokio::test]
+async fn save_table() {
+    let (status, body) = post("/save-table", json!({ "weapon": weapon() })).await;
+    assert_eq!(status, StatusCode::OK);
+    let rows = body["rows"].as_array().unwrap();
+    assert_eq!(rows.len(), 6);
+    assert_eq!(rows[0]["save"], "2+");
+    assert_eq!(rows[5]["save"], "-");
+    assert_close(&rows[2]["average_damage"], 4.0 / 9.0);
+    assert_close(&rows[2]["summary"]["mean"], 4.0 / 9.0);
+}
+
+#[tokio::test]
+async fn simulate_is_repeatable() {
+    let request = json!({ "weapon": weapon(), "target": target(4), "trials": 1000, "seed": 7 });
+    let (status, first) = post("/simulate", request.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["trials"], 1000);
+    assert_eq!(first["seed"], 7);
+    assert_close(&first["exact_mean"], 4.0 / 9.0);
+    let (_, second) = post("/simulate", request).await;
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn out_of_range_values_are_unprocessable() {
+    let mut weapon = weapon();
+    weapon["hit"]["value"] = json!(7);
+    let (status, body) = post(
+        "/simulate",
+        json!({ "weapon": weapon, "target": target(9), "trials": 0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"], "Invalid request");
+    assert_eq!(
+        body["details"],
+        json!([
+            "weapon.hit.value must be between 1 and 6 (got 7)",
+            "target.save.value must be between 1 and 7 (for no save), got 9",
+            "trials must be between 1 and 1000000 (got 0)",
+        ])
+    );
+}
+
+#[tokio::test]
+async fn bonuses_which_overflow_are_unprocessable() {
+    for bonus in [300, i16::MAX as i32] {
+        let mut weapon = weapon();
+        weapon["attacks"]["bonus"] = json!(bonus);
+        let (status, body) = post(
+            "/distribution",
+            json!({ "weapon": weapon, "target": target(4) }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(
+            body["details"][0]
+                .as_str()
+                .unwrap()
+                .starts_with("weapon.attacks.bonus is out of range")
+        );
+    }
+}
+
+#[tokio::test]
+async fn missing_fields_are_unprocessable() {
+    let (status, body) = post("/average", json!({ "weapon": weapon() })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .contains("missing field `target`")
+    );
+}
+
+#[tokio::test]
+async fn malformed_json_is_a_bad_request() {
+    let request = Request::post("/average")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from("{ not json"))
+        .unwrap();
+    let (status, body) = send(request).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn missing_content_type_is_unsupported() {
+    let request = Request::post("/average")
+        .body(Body::from(
+            json!({ "weapon": weapon(), "target": target(4) }).to_string(),
+        ))
+        .unwrap();
+    let (status, _) = send(request).await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[tokio::test]
+async fn openapi_document_lists_every_endpoint() {
+    let request = Request::get("/openapi.json").body(Body::empty()).unwrap();
+    let (status, body) = send(request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["openapi"], "3.1.0");
+    for path in ["/average", "/distribution", "/save-table", "/simulate"] {
+        assert!(body["paths"][path]["post"].is_object(), "{path} is missing");
+    }
+    for (_, schema) in body["components"]["schemas"].as_object().unwrap() {
+        assert!(schema.is_object());
+    }
+}
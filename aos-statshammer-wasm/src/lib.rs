@@ -0,0 +1,189 @@
+//! WebAssembly bindings for the core calculations, for use from JavaScript.
+//!
+//! Weapons and targets are built from plain JS objects in the wire format of
+//! the core crate's `serde` feature, and every result is returned as a plain
+//! JS object in the same format. The TypeScript types for both are generated from
+//! the core types, and included in the generated `.d.ts` file.
+
+use aos_statshammer_core::report::{DistributionReport, SaveTableRow};
+use aos_statshammer_core::target::{self, Save};
+use aos_statshammer_core::{validate, weapon};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use wasm_bindgen::prelude::*;
+
+// The TypeScript declarations for these are generated from the core types (with its
+// `tsify` feature), and included in the `.d.ts` file along with the bindings.
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "WeaponProfile")]
+    pub type WeaponProfile;
+
+    #[wasm_bindgen(typescript_type = "TargetProfile")]
+    pub type TargetProfile;
+
+    #[wasm_bindgen(typescript_type = "DistributionReport")]
+    pub type DamageDistribution;
+
+    #[wasm_bindgen(typescript_type = "DamageBreakdown<number>")]
+    pub type DamageBreakdown;
+
+    #[wasm_bindgen(typescript_type = "SaveTableRow[]")]
+    pub type SaveTableRows;
+}
+
+/// A weapon profile.
+#[wasm_bindgen]
+pub struct Weapon(weapon::Weapon);
+
+#[wasm_bindgen]
+impl Weapon {
+    /// Throws if the profile is malformed or has values out of range.
+    #[wasm_bindgen(constructor)]
+    pub fn new(profile: WeaponProfile) -> Result<Weapon, JsError> {
+        let weapon = from_js(profile.into())?;
+        valid(validate::weapon(&weapon))?;
+        Ok(Self(weapon))
+    }
+
+    /// The profile this weapon was built from (with every default filled in).
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> Result<WeaponProfile, JsError> {
+        to_js(&self.0).map(JsCast::unchecked_into)
+    }
+
+    #[wasm_bindgen(js_name = averageDamage)]
+    pub fn average_damage(&self, target: &Target) -> f64 {
+        self.0.average_damage(&target.0)
+    }
+
+    /// The average at each stage of the attack sequence against `target`.
+    #[wasm_bindgen(js_name = damageBreakdown)]
+    pub fn breakdown(&self, target: &Target) -> Result<DamageBreakdown, JsError> {
+        to_js(&self.0.breakdown(&target.0)).map(JsCast::unchecked_into)
+    }
+
+    /// The exact distribution of the damage dealt to `target`.
+    #[wasm_bindgen(js_name = damageDistribution)]
+    pub fn damage_distribution(&self, target: &Target) -> Result<DamageDistribution, JsError> {
+        let report = DistributionReport::from(&self.0.damage_distribution(&target.0));
+        to_js(&report).map(JsCast::unchecked_into)
+    }
+
+    /// The average damage against every save, from 2+ down to no save. Only the
+    /// modifiers of `target` are used (it defaults to having none at all).
+    #[wasm_bindgen(js_name = saveTable)]
+    pub fn save_table(&self, target: Option<Target>) -> Result<SaveTableRows, JsError> {
+        let target = target.map_or_else(|| target::Target::from(Save::NONE), |t| t.0);
+        to_js(&SaveTableRow::for_weapon(&self.0, &target)).map(JsCast::unchecked_into)
+    }
+}
+
+/// The target of an attack.
+#[wasm_bindgen]
+pub struct Target(target::Target);
+
+#[wasm_bindgen]
+impl Target {
+    /// Throws if the profile is malformed or has values out of range.
+    #[wasm_bindgen(constructor)]
+    pub fn new(profile: TargetProfile) -> Result<Target, JsError> {
+        let target = from_js(profile.into())?;
+        valid(validate::target(&target))?;
+        Ok(Self(target))
+    }
+
+    /// A target with the given save characteristic and no other abilities.
+    /// Throws if the save is out of range.
+    #[wasm_bindgen(js_name = fromSave)]
+    pub fn from_save(save: u8) -> Result<Target, JsError> {
+        let target = target::Target::from(save);
+        valid(validate::target(&target))?;
+        Ok(Self(target))
+    }
+
+    /// The profile this target was built from (with every default filled in).
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> Result<TargetProfile, JsError> {
+        to_js(&self.0).map(JsCast::unchecked_into)
+    }
+}
+
+/// An error listing every problem, if there are any.
+fn valid(problems: Vec<String>) -> Result<(), JsError> {
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(JsError::new(&format!(
+            "Invalid profile: {}",
+            problems.join("; ")
+        )))
+    }
+}
+
+fn from_js<T: DeserializeOwned>(value: JsValue) -> Result<T, JsError> {
+    serde_wasm_bindgen::from_value(value).map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    // Plain objects rather than `Map`s, so results can be passed to `JSON.stringify`
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    value
+        .serialize(&serializer)
+        .map_err(|err| JsError::new(&err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aos_statshammer_core::weapon::WeaponBuilder;
+    use tsify::Tsify;
+
+    fn weapon() -> weapon::Weapon {
+        WeaponBuilder::default()
+            .attacks(2)
+            .hit(3)
+            .wound(4)
+            .rend(1)
+            .damage(1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn typescript_types_are_generated() {
+        let declarations = [
+            <weapon::Weapon as Tsify>::DECL,
+            <target::Target as Tsify>::DECL,
+            <DistributionReport as Tsify>::DECL,
+            <weapon::DamageBreakdown as Tsify>::DECL,
+            <SaveTableRow as Tsify>::DECL,
+        ];
+        let names = [
+            "WeaponProfile",
+            "TargetProfile",
+            "DistributionReport",
+            "DamageBreakdown<P>",
+            "SaveTableRow",
+        ];
+        for (declaration, name) in declarations.iter().zip(names) {
+            assert!(
+                declaration.starts_with(&format!("export interface {name} {{")),
+                "{declaration}"
+            );
+        }
+        assert!(<target::Target as Tsify>::DECL.contains("ward?: number | null;"));
+    }
+
+    #[test]
+    fn results_use_the_core_wire_format() {
+        let target = target::Target::from(4);
+        let report = DistributionReport::from(&weapon().damage_distribution(&target));
+        assert_eq!(report.probability.len(), 3);
+        assert_eq!(report.at_least.len(), 3);
+        let rows = SaveTableRow::for_weapon(&weapon(), &target::Target::from(Save::NONE));
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0].save, "2+");
+        assert_eq!(rows[5].save, "-");
+    }
+}
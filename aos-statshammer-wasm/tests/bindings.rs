@@ -0,0 +1,75 @@
+//! Tests for the bindings themselves, which need a JavaScript engine:
+//! `wasm-pack test --node aos-statshammer-wasm` (or `cargo test --target
+//! wasm32-unknown-unknown` with `wasm-bindgen-test-runner` installed).
+
+#![cfg(target_arch = "wasm32")]
+
+use aos_statshammer_wasm::{Target, Weapon};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn parse<T: JsCast>(json: &str) -> T {
+    js_sys::JSON::parse(json).unwrap().unchecked_into()
+}
+
+fn stringify(value: &JsValue) -> String {
+    js_sys::JSON::stringify(value).unwrap().into()
+}
+
+fn message(err: JsError) -> String {
+    js_sys::Error::from(JsValue::from(err)).message().into()
+}
+
+#[wasm_bindgen_test]
+fn profiles_round_trip_with_every_default_filled_in() {
+    let weapon = Weapon::new(parse(
+        r#"{"attacks": {"value": 2}, "hit": {"value": 3, "reroll": "ones"}, "wound": {"value": 4},
+            "rend": {"value": 1}, "damage": {"value": 1}}"#,
+    ))
+    .unwrap();
+    assert_eq!(
+        stringify(&weapon.to_json().unwrap()),
+        r#"{"attacks":{"value":2,"bonus":0},"hit":{"value":3,"bonus":0,"reroll":"ones","exploding":null},"wound":{"value":4,"bonus":0,"reroll":null,"exploding":null},"rend":{"value":1,"bonus":0},"damage":{"value":1,"bonus":0}}"#
+    );
+
+    let target = Target::new(parse(r#"{"save": {"value": 4}, "ward": 5}"#)).unwrap();
+    let json = stringify(&target.to_json().unwrap());
+    assert_eq!(
+        json,
+        r#"{"save":{"value":4,"bonus":0,"reroll":null},"ethereal":false,"ward":5,"health":1,"models":1}"#
+    );
+    let target = Target::new(parse(&json)).unwrap();
+    assert_eq!(stringify(&target.to_json().unwrap()), json);
+}
+
+#[wasm_bindgen_test]
+fn invalid_profiles_list_every_problem() {
+    let err = Weapon::new(parse(
+        r#"{"attacks": {"value": 0}, "hit": {"value": 7}, "wound": {"value": 4},
+            "rend": {"value": 1}, "damage": {"value": 1}}"#,
+    ))
+    .err()
+    .unwrap();
+    assert_eq!(
+        message(err),
+        "Invalid profile: weapon.attacks.value must be at least 1; \
+         weapon.hit.value must be between 1 and 6 (got 7)"
+    );
+
+    let err = Target::new(parse(r#"{"save": {"value": 4}, "ward": 1}"#))
+        .err()
+        .unwrap();
+    assert_eq!(
+        message(err),
+        "Invalid profile: target.ward must be between 2 and 6 (got 1)"
+    );
+    assert!(Target::from_save(0).is_err());
+}
+
+#[wasm_bindgen_test]
+fn malformed_profiles_are_errors() {
+    let err = Weapon::new(parse(r#"{"attacks": {"value": 2}}"#))
+        .err()
+        .unwrap();
+    assert!(message(err).contains("missing field `hit`"));
+}